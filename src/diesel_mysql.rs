@@ -0,0 +1,226 @@
+//! Serialization to/from MySQL
+//!
+//! Values are exchanged as UTC wall clock times. MySQL converts `TIMESTAMP` columns between the
+//! session time zone and UTC, so connections reading or writing them should set
+//! `time_zone = '+00:00'`; `DATETIME` columns are stored as given.
+//!
+//! MySQL stores at most microsecond precision (with `DATETIME(6)` or `TIMESTAMP(6)`), so any
+//! nanoseconds are truncated when writing; wrap values in [`Rounded`] or [`Strict`] to round or
+//! refuse them instead. Columns declared with a lower precision are rounded by the server.
+
+use diesel::deserialize::FromSql;
+use diesel::deserialize::Result as DeserializeResult;
+use diesel::mysql::Mysql;
+use diesel::mysql::MysqlValue;
+use diesel::mysql::data_types::MysqlTime;
+use diesel::mysql::data_types::MysqlTimestampType;
+use diesel::serialize::Output;
+use diesel::serialize::Result as SerializeResult;
+use diesel::serialize::ToSql;
+use diesel::sql_types;
+
+use crate::DateTime;
+use crate::Precision;
+use crate::PrecisionLoss;
+use crate::Rounded;
+use crate::Strict;
+use crate::interval::TimeInterval;
+use crate::parse::days_in_month;
+use crate::storage::Stored;
+
+/// Implement `ToSql` for `DATETIME` and `TIMESTAMP`, following each type's precision-loss policy.
+macro_rules! to_sql {
+  ($($ty:ty),*) => {$(
+    impl ToSql<sql_types::Datetime, Mysql> for $ty {
+      fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Mysql>) -> SerializeResult {
+        let (dt, policy) = self.stored();
+        ToSql::<sql_types::Datetime, Mysql>::to_sql(&dt.mysql_time(policy)?, &mut out.reborrow())
+      }
+    }
+
+    impl ToSql<sql_types::Timestamp, Mysql> for $ty {
+      fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Mysql>) -> SerializeResult {
+        let (dt, policy) = self.stored();
+        ToSql::<sql_types::Timestamp, Mysql>::to_sql(&dt.mysql_time(policy)?, &mut out.reborrow())
+      }
+    }
+  )*};
+}
+
+to_sql!(DateTime, Rounded, Strict);
+
+impl FromSql<sql_types::Datetime, Mysql> for DateTime {
+  fn from_sql(value: MysqlValue<'_>) -> DeserializeResult<Self> {
+    from_mysql_time(FromSql::<sql_types::Datetime, Mysql>::from_sql(value)?)
+  }
+}
+
+impl FromSql<sql_types::Timestamp, Mysql> for DateTime {
+  fn from_sql(value: MysqlValue<'_>) -> DeserializeResult<Self> {
+    from_mysql_time(FromSql::<sql_types::Timestamp, Mysql>::from_sql(value)?)
+  }
+}
+
+impl DateTime {
+  /// Convert the UTC wall clock time of this date and time to a `MysqlTime`, following `policy`
+  /// for any nanoseconds.
+  fn mysql_time(&self, policy: PrecisionLoss) -> Result<MysqlTime, String> {
+    let utc = DateTime::from_timestamp(self.seconds, self.nanos)
+      .to_storage_precision(Precision::Microsecond, policy)?;
+    if !(0..=9999).contains(&utc.year()) {
+      return Err(format!("{utc:?} is outside the range MySQL supports"));
+    }
+    Ok(MysqlTime::new(
+      utc.year() as u32,
+      utc.month().into(),
+      utc.day().into(),
+      utc.hour().into(),
+      utc.minute().into(),
+      utc.second().into(),
+      (utc.nanosecond() / 1_000).into(),
+      false,
+      MysqlTimestampType::MYSQL_TIMESTAMP_DATETIME,
+      0,
+    ))
+  }
+}
+
+/// Convert a `MysqlTime` to a date and time, treating it as UTC.
+fn from_mysql_time(time: MysqlTime) -> DeserializeResult<DateTime> {
+  let valid = time.year <= 9999
+    && (1..=12).contains(&time.month)
+    && time.day >= 1
+    && time.day <= days_in_month(time.year as i16, time.month as u8).into()
+    && time.hour < 24
+    && time.minute < 60
+    && time.second < 60
+    && time.second_part < 1_000_000;
+  if !valid {
+    return Err(
+      format!(
+        "Invalid MySQL date and time: {:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}",
+        time.year, time.month, time.day, time.hour, time.minute, time.second, time.second_part,
+      )
+      .into(),
+    );
+  }
+  let dt = DateTime::ymd(time.year as i16, time.month as u8, time.day as u8)
+    .hms(time.hour as u8, time.minute as u8, time.second as u8)
+    .nanos(time.second_part as u32 * 1_000);
+  Ok(match time.time_type {
+    MysqlTimestampType::MYSQL_TIMESTAMP_DATETIME_TZ =>
+      dt.build() - TimeInterval::new(time.time_zone_displacement.into(), 0),
+    _ => dt.build(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+  use diesel::mysql::MysqlType;
+  use diesel::query_builder::BindCollector;
+  use diesel::query_builder::bind_collector::RawBytesBindCollector;
+  use diesel::sql_types::HasSqlType;
+
+  use super::*;
+
+  /// Encode a value with its `ToSql` implementation, as Diesel does before sending it to MySQL.
+  fn encode<T, U: ToSql<T, Mysql>>(value: &U) -> Vec<u8>
+  where
+    Mysql: HasSqlType<T>, {
+    let mut collector = RawBytesBindCollector::<Mysql>::default();
+    collector.push_bound_value::<T, U>(value, &mut ()).expect("value should encode");
+    collector.binds.remove(0).expect("value should not be null")
+  }
+
+  fn decode<T>(bytes: &[u8], tpe: MysqlType) -> DeserializeResult<DateTime>
+  where
+    DateTime: FromSql<T, Mysql>, {
+    FromSql::<T, Mysql>::from_sql(MysqlValue::new(bytes, tpe))
+  }
+
+  fn mysql_time(bytes: &[u8]) -> MysqlTime {
+    FromSql::<sql_types::Datetime, Mysql>::from_sql(MysqlValue::new(bytes, MysqlType::DateTime))
+      .expect("bytes should decode")
+  }
+
+  #[test]
+  fn test_round_trip() -> DeserializeResult<()> {
+    for dt in [
+      datetime! { 2012-04-21 15:00:00 },
+      datetime! { 2012-04-21 15:00:00.123456 },
+      datetime! { 1000-01-01 00:00:00 },
+      datetime! { 9999-12-31 23:59:59.999999 },
+    ] {
+      let bytes = encode::<sql_types::Datetime, _>(&dt);
+      check!(decode::<sql_types::Datetime>(&bytes, MysqlType::DateTime)? == dt);
+      let bytes = encode::<sql_types::Timestamp, _>(&dt);
+      check!(decode::<sql_types::Timestamp>(&bytes, MysqlType::Timestamp)? == dt);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_encoding() {
+    let time = mysql_time(&encode::<sql_types::Datetime, _>(&datetime! { 2012-04-21 15:00:00.5 }));
+    check!((time.year, time.month, time.day) == (2012, 4, 21));
+    check!((time.hour, time.minute, time.second, time.second_part) == (15, 0, 0, 500_000));
+    check!(time.time_type == MysqlTimestampType::MYSQL_TIMESTAMP_DATETIME);
+
+    // Nanoseconds are truncated unless the value says otherwise.
+    let dt = datetime! { 2012-04-21 15:00:00.123456789 };
+    let time = mysql_time(&encode::<sql_types::Datetime, _>(&dt));
+    check!(time.second_part == 123_456);
+    let time = mysql_time(&encode::<sql_types::Timestamp, _>(&Rounded(dt)));
+    check!(time.second_part == 123_457);
+    check!(
+      RawBytesBindCollector::<Mysql>::default()
+        .push_bound_value::<sql_types::Datetime, _>(&Strict(dt), &mut ())
+        .is_err()
+    );
+    let dt = datetime! { 2012-04-21 15:00:59.9999995 };
+    let time = mysql_time(&encode::<sql_types::Datetime, _>(&Rounded(dt)));
+    check!((time.minute, time.second, time.second_part) == (1, 0, 0));
+
+    check!(
+      RawBytesBindCollector::<Mysql>::default()
+        .push_bound_value::<sql_types::Datetime, _>(&datetime! { -0001-01-01 00:00:00 }, &mut ())
+        .is_err()
+    );
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_utc() {
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    let time = mysql_time(&encode::<sql_types::Datetime, _>(&dt));
+    check!((time.hour, time.time_zone_displacement) == (15, 0));
+  }
+
+  #[test]
+  fn test_decode() -> DeserializeResult<()> {
+    let time = |year, month, day, time_type, displacement| {
+      MysqlTime::new(year, month, day, 11, 0, 0, 0, false, time_type, displacement)
+    };
+    let datetime = MysqlTimestampType::MYSQL_TIMESTAMP_DATETIME;
+    let bytes = encode::<sql_types::Datetime, _>(&time(2012, 4, 21, datetime, 0));
+    check!(
+      decode::<sql_types::Datetime>(&bytes, MysqlType::DateTime)?
+        == datetime! { 2012-04-21 11:00:00 }
+    );
+
+    let tz = MysqlTimestampType::MYSQL_TIMESTAMP_DATETIME_TZ;
+    let bytes = encode::<sql_types::Datetime, _>(&time(2012, 4, 21, tz, -14_400));
+    check!(
+      decode::<sql_types::Datetime>(&bytes, MysqlType::DateTime)?
+        == datetime! { 2012-04-21 15:00:00 }
+    );
+
+    for (year, month, day) in [(0, 0, 0), (2012, 2, 30), (2012, 13, 1)] {
+      let bytes = encode::<sql_types::Datetime, _>(&time(year, month, day, datetime, 0));
+      check!(decode::<sql_types::Datetime>(&bytes, MysqlType::DateTime).is_err());
+    }
+    check!(decode::<sql_types::Datetime>(&[0; 4], MysqlType::Long).is_err());
+    Ok(())
+  }
+}
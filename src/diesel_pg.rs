@@ -1,50 +1,988 @@
 //! Serialization to/from PostgreSQL
+//!
+//! With the `tz` feature, `TIMESTAMPTZ` values are loaded in UTC; use `InZone` to convert them
+//! into another time zone instead. PostgreSQL's `infinity` and `-infinity` map to
+//! [`DateTime::MAX`] and [`DateTime::MIN`] in both directions, and finite timestamps outside that
+//! range are an error.
+//!
+//! PostgreSQL stores microseconds, so any nanoseconds are truncated; wrap values in
+//! [`Rounded`] or [`Strict`] to round or refuse them instead.
+//!
+//! [`TimeInterval`]s map to `INTERVAL` as a number of microseconds, with no day or month
+//! component; any nanoseconds are truncated toward zero. When reading, a day counts as exactly 24
+//! hours (as with `EXTRACT(EPOCH FROM ...)`), but intervals with a month component are an error,
+//! since the length of a month depends on which month it is.
+//!
+//! [`DateTimeRange`]s map to `TSTZRANGE` and `TSRANGE`, with each bound converted as above.
+
+use std::io::Write;
+#[cfg(feature = "tz")]
+use std::marker::PhantomData;
+use std::ops::Bound;
 
 use diesel::deserialize::FromSql;
 use diesel::deserialize::Result as DeserializeResult;
 use diesel::pg::Pg;
 use diesel::pg::PgValue;
+use diesel::pg::data_types::PgInterval;
 use diesel::pg::data_types::PgTimestamp;
+use diesel::serialize::IsNull;
 use diesel::serialize::Output;
 use diesel::serialize::Result as SerializeResult;
 use diesel::serialize::ToSql;
 use diesel::sql_types;
 
 use crate::DateTime;
+use crate::PrecisionLoss;
+use crate::Rounded;
+use crate::Strict;
 use crate::interval::TimeInterval;
+use crate::range::DateTimeRange;
+use crate::storage::Stored;
+
+/// Implement `ToSql` for `TIMESTAMP` and `TIMESTAMPTZ`, following each type's precision-loss
+/// policy.
+macro_rules! to_sql {
+  ($($ty:ty),*) => {$(
+    impl ToSql<sql_types::Timestamp, Pg> for $ty {
+      fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Pg>) -> SerializeResult {
+        let (dt, policy) = self.stored();
+        let timestamp = PgTimestamp(dt.pg_timestamp(policy)?);
+        ToSql::<sql_types::Timestamp, Pg>::to_sql(&timestamp, &mut out.reborrow())
+      }
+    }
+
+    impl ToSql<sql_types::Timestamptz, Pg> for $ty {
+      fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Pg>) -> SerializeResult {
+        ToSql::<sql_types::Timestamp, Pg>::to_sql(self, out)
+      }
+    }
+  )*};
+}
+
+to_sql!(DateTime, Rounded, Strict);
 
-impl ToSql<sql_types::Timestamp, Pg> for DateTime {
+impl FromSql<sql_types::Timestamp, Pg> for DateTime {
+  fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+    let PgTimestamp(micros) = FromSql::<sql_types::Timestamp, Pg>::from_sql(bytes)?;
+    Ok(DateTime::from_pg_timestamp(micros)?)
+  }
+}
+
+impl FromSql<sql_types::Timestamptz, Pg> for DateTime {
+  fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+    let PgTimestamp(micros) = FromSql::<sql_types::Timestamp, Pg>::from_sql(bytes)?;
+    Ok(DateTime::from_pg_timestamp(micros)?.pg_utc())
+  }
+}
+
+/// A time zone that `TIMESTAMPTZ` values can be converted into as they are loaded.
+///
+/// ## Example
+///
+/// ```
+/// use datetime::diesel_pg::Zone;
+/// use datetime::tz;
+///
+/// struct Eastern;
+///
+/// impl Zone for Eastern {
+///   const TZ: tz::TimeZoneRef<'static> = tz::us::EASTERN;
+/// }
+/// ```
+#[cfg(feature = "tz")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tz")))]
+pub trait Zone {
+  /// The time zone to convert into.
+  const TZ: crate::tz::TimeZoneRef<'static>;
+}
+
+/// A `TIMESTAMPTZ` value converted into the time zone `Z` as it is loaded.
+///
+/// Use this with `deserialize_as` to load a [`DateTime`] field in a particular time zone, rather
+/// than UTC:
+///
+/// ```
+/// # use datetime::diesel_pg::Zone;
+/// use datetime::DateTime;
+/// use datetime::diesel_pg::InZone;
+/// # use datetime::tz;
+/// use diesel::prelude::*;
+///
+/// # struct Eastern;
+/// # impl Zone for Eastern {
+/// #   const TZ: tz::TimeZoneRef<'static> = tz::us::EASTERN;
+/// # }
+/// diesel::table! {
+///   events (id) {
+///     id -> Integer,
+///     starts_at -> Timestamptz,
+///   }
+/// }
+///
+/// #[derive(Queryable)]
+/// struct Event {
+///   id: i32,
+///   #[diesel(deserialize_as = InZone<Eastern>)]
+///   starts_at: DateTime,
+/// }
+/// ```
+#[cfg(feature = "tz")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tz")))]
+#[derive(Clone, Copy, Debug, diesel::FromSqlRow)]
+pub struct InZone<Z>(pub DateTime, PhantomData<Z>);
+
+#[cfg(feature = "tz")]
+impl<Z: Zone> FromSql<sql_types::Timestamptz, Pg> for InZone<Z> {
+  fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+    let dt: DateTime = FromSql::<sql_types::Timestamptz, Pg>::from_sql(bytes)?;
+    Ok(Self(dt.with_tz(Z::TZ), PhantomData))
+  }
+}
+
+#[cfg(feature = "tz")]
+impl<Z> From<InZone<Z>> for DateTime {
+  fn from(dt: InZone<Z>) -> Self {
+    dt.0
+  }
+}
+
+impl ToSql<sql_types::Interval, Pg> for TimeInterval {
   fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Pg>) -> SerializeResult {
-    let micros_from_epoch = (*self - PG_EPOCH).as_microseconds();
-    ToSql::<sql_types::Timestamp, Pg>::to_sql(&PgTimestamp(micros_from_epoch), &mut out.reborrow())
+    let interval = PgInterval { microseconds: self.pg_microseconds()?, days: 0, months: 0 };
+    ToSql::<sql_types::Interval, Pg>::to_sql(&interval, &mut out.reborrow())
+  }
+}
+
+impl FromSql<sql_types::Interval, Pg> for TimeInterval {
+  fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
+    let PgInterval { microseconds, days, months } =
+      FromSql::<sql_types::Interval, Pg>::from_sql(bytes)?;
+    Ok(TimeInterval::from_pg_interval(microseconds, days, months)?)
   }
 }
 
-impl ToSql<sql_types::Timestamptz, Pg> for DateTime {
+impl ToSql<sql_types::Tstzrange, Pg> for DateTimeRange {
   fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Pg>) -> SerializeResult {
-    ToSql::<sql_types::Timestamp, Pg>::to_sql(self, out)
+    self.write_pg_range(out)
   }
 }
 
-impl FromSql<sql_types::Timestamp, Pg> for DateTime {
+impl ToSql<sql_types::Tsrange, Pg> for DateTimeRange {
+  fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Pg>) -> SerializeResult {
+    self.write_pg_range(out)
+  }
+}
+
+impl FromSql<sql_types::Tstzrange, Pg> for DateTimeRange {
   fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
-    let PgTimestamp(micros) = FromSql::<diesel::sql_types::Timestamp, Pg>::from_sql(bytes)?;
-    let seconds = micros.div_euclid(1_000_000);
-    let micros = match micros.signum() {
-      0 => 0,
-      1 => micros % 1_000_000,
-      -1 => 1_000_000 - (micros % 1_000_000).abs(),
-      _ => unreachable!("signum always returns -1, 0, or 1"),
-    };
-    let duration = TimeInterval::new(seconds, micros as u32 * 1_000);
-    Ok(PG_EPOCH + duration)
+    DateTimeRange::read_pg_range(bytes.as_bytes(), |micros| {
+      DateTime::from_pg_timestamp(micros).map(DateTime::pg_utc)
+    })
   }
 }
 
-impl FromSql<sql_types::Timestamptz, Pg> for DateTime {
+impl FromSql<sql_types::Tsrange, Pg> for DateTimeRange {
   fn from_sql(bytes: PgValue<'_>) -> DeserializeResult<Self> {
-    FromSql::<sql_types::Timestamp, Pg>::from_sql(bytes)
+    DateTimeRange::read_pg_range(bytes.as_bytes(), DateTime::from_pg_timestamp)
+  }
+}
+
+// Flags in PostgreSQL's binary range format.
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+impl DateTimeRange {
+  /// Write this range in PostgreSQL's binary range format: a flags byte, followed by each finite
+  /// bound as a length-prefixed timestamp.
+  fn write_pg_range(&self, out: &mut impl Write) -> SerializeResult {
+    if self.is_empty() {
+      out.write_all(&[RANGE_EMPTY])?;
+      return Ok(IsNull::No);
+    }
+    let (start_flags, start) = match self.start() {
+      Bound::Included(dt) => (RANGE_LB_INC, Some(dt)),
+      Bound::Excluded(dt) => (0, Some(dt)),
+      Bound::Unbounded => (RANGE_LB_INF, None),
+    };
+    let (end_flags, end) = match self.end() {
+      Bound::Included(dt) => (RANGE_UB_INC, Some(dt)),
+      Bound::Excluded(dt) => (0, Some(dt)),
+      Bound::Unbounded => (RANGE_UB_INF, None),
+    };
+    out.write_all(&[start_flags | end_flags])?;
+    for dt in [start, end].into_iter().flatten() {
+      let micros = dt.pg_timestamp(PrecisionLoss::Truncate)?;
+      out.write_all(&8_i32.to_be_bytes())?;
+      out.write_all(&micros.to_be_bytes())?;
+    }
+    Ok(IsNull::No)
+  }
+
+  /// Read a range in PostgreSQL's binary range format, converting each bound with `read`.
+  fn read_pg_range(
+    bytes: &[u8], read: fn(i64) -> Result<DateTime, String>,
+  ) -> DeserializeResult<Self> {
+    let Some((&flags, mut bytes)) = bytes.split_first() else {
+      return Err("Range is missing its flags".into());
+    };
+    if flags & RANGE_EMPTY != 0 {
+      return Ok(DateTimeRange::EMPTY);
+    }
+    let mut bound = |infinite, inclusive| -> DeserializeResult<Bound<DateTime>> {
+      if flags & infinite != 0 {
+        return Ok(Bound::Unbounded);
+      }
+      let Some((&[0, 0, 0, 8], rest)) = bytes.split_first_chunk::<4>() else {
+        return Err("Range bound is not an 8-byte timestamp".into());
+      };
+      let Some((micros, rest)) = rest.split_first_chunk::<8>() else {
+        return Err("Range bound is truncated".into());
+      };
+      bytes = rest;
+      let dt = read(i64::from_be_bytes(*micros))?;
+      Ok(if flags & inclusive != 0 { Bound::Included(dt) } else { Bound::Excluded(dt) })
+    };
+    let start = bound(RANGE_LB_INF, RANGE_LB_INC)?;
+    let end = bound(RANGE_UB_INF, RANGE_UB_INC)?;
+    if !bytes.is_empty() {
+      return Err("Range has trailing bytes".into());
+    }
+    Ok(DateTimeRange::new(start, end))
+  }
+}
+
+/// Typed SQL functions and operators for `TIMESTAMP` and `TIMESTAMPTZ` expressions.
+///
+/// Diesel already provides `col + interval`, `col - interval`, and `col.at_time_zone(name)`; their
+/// results load as [`DateTime`], and a [`TimeInterval`] can be used as the interval. This module
+/// adds the rest, with result types that load as [`DateTime`], [`TimeInterval`], or `f64`.
+///
+/// ## Example
+///
+/// ```
+/// use datetime::diesel_pg::dsl::*;
+/// use datetime::time_interval;
+/// use diesel::debug_query;
+/// use diesel::pg::Pg;
+/// use diesel::prelude::*;
+///
+/// diesel::table! {
+///   events (id) {
+///     id -> Integer,
+///     starts_at -> Timestamptz,
+///     ends_at -> Timestamptz,
+///   }
+/// }
+///
+/// let query = events::table
+///   .select((date_trunc(Unit::Hour, events::starts_at), events::ends_at.since(events::starts_at)))
+///   .filter((events::starts_at + time_interval!(1h)).gt(now()));
+/// assert_eq!(
+///   debug_query::<Pg, _>(&query).to_string(),
+///   "SELECT date_trunc($1, \"events\".\"starts_at\"), (\"events\".\"ends_at\" - \
+///    \"events\".\"starts_at\") FROM \"events\" WHERE ((\"events\".\"starts_at\" + $2) > now()) \
+///    -- binds: [Hour, TimeInterval { seconds: 3600, nanos: 0 }]",
+/// );
+/// ```
+pub mod dsl {
+  use diesel::expression::AppearsOnTable;
+  use diesel::expression::AsExpression;
+  use diesel::expression::Expression;
+  use diesel::expression::SelectableExpression;
+  use diesel::expression::ValidGrouping;
+  use diesel::pg::Pg;
+  use diesel::query_builder::AstPass;
+  use diesel::query_builder::QueryFragment;
+  use diesel::query_builder::QueryId;
+  use diesel::result::QueryResult;
+  use diesel::serialize::Output;
+  use diesel::serialize::Result as SerializeResult;
+  use diesel::serialize::ToSql;
+  use diesel::sql_types::Bool;
+  use diesel::sql_types::Double;
+  use diesel::sql_types::Interval;
+  use diesel::sql_types::Nullable;
+  use diesel::sql_types::SingleValue;
+  use diesel::sql_types::SqlType;
+  use diesel::sql_types::Text;
+  use diesel::sql_types::Timestamp;
+  use diesel::sql_types::Timestamptz;
+  use diesel::sql_types::Tsrange;
+  use diesel::sql_types::Tstzrange;
+  use diesel::sql_types::is_nullable::NotNull;
+
+  /// A `TIMESTAMP` or `TIMESTAMPTZ` SQL type, or a nullable one.
+  pub trait TimestampLike: SingleValue {
+    /// The SQL type of the difference between two values of this type.
+    type Interval: SingleValue;
+    /// The SQL type of a field extracted from a value of this type.
+    type Double: SingleValue;
+  }
+
+  impl TimestampLike for Timestamp {
+    type Double = Double;
+    type Interval = Interval;
+  }
+
+  impl TimestampLike for Timestamptz {
+    type Double = Double;
+    type Interval = Interval;
+  }
+
+  impl<T> TimestampLike for Nullable<T>
+  where
+    T: TimestampLike + SqlType<IsNull = NotNull>,
+    T::Interval: SqlType<IsNull = NotNull>,
+    T::Double: SqlType<IsNull = NotNull>,
+  {
+    type Double = Nullable<T::Double>;
+    type Interval = Nullable<T::Interval>;
+  }
+
+  /// A `TSTZRANGE` or `TSRANGE` SQL type, or a nullable one.
+  pub trait RangeLike: SingleValue {
+    /// The SQL type of a comparison between two values of this type.
+    type Bool: SingleValue;
+  }
+
+  impl RangeLike for Tstzrange {
+    type Bool = Bool;
+  }
+
+  impl RangeLike for Tsrange {
+    type Bool = Bool;
+  }
+
+  impl<T> RangeLike for Nullable<T>
+  where
+    T: RangeLike + SqlType<IsNull = NotNull>,
+    T::Bool: SqlType<IsNull = NotNull>,
+  {
+    type Bool = Nullable<T::Bool>;
+  }
+
+  diesel::define_sql_function! {
+    /// Truncate a timestamp to the given [`Unit`], like `date_trunc('hour', col)`.
+    fn date_trunc<ST: TimestampLike>(unit: Text, source: ST) -> ST;
+  }
+
+  diesel::define_sql_function! {
+    /// Extract a [`Field`] from a timestamp as a `double precision`, like
+    /// `EXTRACT(hour FROM col)`.
+    #[sql_name = "date_part"]
+    fn extract<ST: TimestampLike>(field: Text, source: ST) -> ST::Double;
+  }
+
+  diesel::define_sql_function! {
+    /// The start time of the current transaction.
+    fn now() -> Timestamptz;
+  }
+
+  macro_rules! keywords {
+    ($(#[$attr:meta])* $name:ident { $($variant:ident => $keyword:literal,)* }) => {
+      $(#[$attr])*
+      #[derive(Clone, Copy, Debug, Eq, PartialEq, diesel::AsExpression)]
+      #[diesel(sql_type = Text)]
+      pub enum $name {
+        $(#[doc = concat!("`", $keyword, "`")] $variant,)*
+      }
+
+      impl $name {
+        /// The PostgreSQL keyword.
+        pub const fn as_str(&self) -> &'static str {
+          match self {
+            $(Self::$variant => $keyword,)*
+          }
+        }
+      }
+
+      impl ToSql<Text, Pg> for $name {
+        fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Pg>) -> SerializeResult {
+          ToSql::<Text, Pg>::to_sql(self.as_str(), out)
+        }
+      }
+    };
+  }
+
+  keywords! {
+    /// A precision to truncate to with [`date_trunc()`].
+    Unit {
+      Microseconds => "microseconds",
+      Milliseconds => "milliseconds",
+      Second => "second",
+      Minute => "minute",
+      Hour => "hour",
+      Day => "day",
+      Week => "week",
+      Month => "month",
+      Quarter => "quarter",
+      Year => "year",
+      Decade => "decade",
+      Century => "century",
+      Millennium => "millennium",
+    }
+  }
+
+  keywords! {
+    /// A field to extract with [`extract()`].
+    Field {
+      Microseconds => "microseconds",
+      Milliseconds => "milliseconds",
+      Second => "second",
+      Minute => "minute",
+      Hour => "hour",
+      Day => "day",
+      DayOfWeek => "dow",
+      IsoDayOfWeek => "isodow",
+      DayOfYear => "doy",
+      Week => "week",
+      Month => "month",
+      Quarter => "quarter",
+      Year => "year",
+      IsoYear => "isoyear",
+      Decade => "decade",
+      Century => "century",
+      Millennium => "millennium",
+      Epoch => "epoch",
+      Julian => "julian",
+      Timezone => "timezone",
+      TimezoneHour => "timezone_hour",
+      TimezoneMinute => "timezone_minute",
+    }
+  }
+
+  /// Methods on `TIMESTAMP` and `TIMESTAMPTZ` expressions.
+  pub trait DateTimeExpressionMethods: Expression + Sized
+  where
+    Self::SqlType: TimestampLike, {
+    /// The interval from `other` until this timestamp, like `col - other`.
+    fn since<T: AsExpression<Self::SqlType>>(self, other: T) -> Since<Self, T::Expression> {
+      Since { left: self, right: other.as_expression() }
+    }
+  }
+
+  impl<T> DateTimeExpressionMethods for T
+  where
+    T: Expression,
+    T::SqlType: TimestampLike,
+  {
+  }
+
+  /// The interval between two timestamps; see [`DateTimeExpressionMethods::since`].
+  #[derive(Clone, Copy, Debug, QueryId, ValidGrouping)]
+  pub struct Since<L, R> {
+    left: L,
+    right: R,
+  }
+
+  impl<L, R> Expression for Since<L, R>
+  where
+    L: Expression,
+    L::SqlType: TimestampLike,
+    R: Expression<SqlType = L::SqlType>,
+  {
+    type SqlType = <L::SqlType as TimestampLike>::Interval;
+  }
+
+  impl<L, R> QueryFragment<Pg> for Since<L, R>
+  where
+    L: QueryFragment<Pg>,
+    R: QueryFragment<Pg>,
+  {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+      out.push_sql("(");
+      self.left.walk_ast(out.reborrow())?;
+      out.push_sql(" - ");
+      self.right.walk_ast(out.reborrow())?;
+      out.push_sql(")");
+      Ok(())
+    }
+  }
+
+  impl<L, R, QS> AppearsOnTable<QS> for Since<L, R>
+  where
+    L: AppearsOnTable<QS>,
+    R: AppearsOnTable<QS>,
+    Self: Expression,
+  {
+  }
+
+  impl<L, R, QS> SelectableExpression<QS> for Since<L, R>
+  where
+    L: SelectableExpression<QS>,
+    R: SelectableExpression<QS>,
+    Self: AppearsOnTable<QS>,
+  {
+  }
+
+  /// Methods on `TSTZRANGE` and `TSRANGE` expressions.
+  ///
+  /// Diesel's own `contains` checks whether a range contains a single timestamp; these compare
+  /// two ranges. They are prefixed with `range_` so as not to collide with the methods newer
+  /// versions of Diesel provide for every range type.
+  pub trait DateTimeRangeExpressionMethods: Expression + Sized
+  where
+    Self::SqlType: RangeLike, {
+    /// Whether this range and `other` have any time in common, like `col && other`.
+    fn range_overlaps<T: AsExpression<Self::SqlType>>(
+      self, other: T,
+    ) -> RangeOverlaps<Self, T::Expression> {
+      RangeOverlaps { left: self, right: other.as_expression() }
+    }
+
+    /// Whether this range contains all of `other`, like `col @> other`.
+    fn range_contains<T: AsExpression<Self::SqlType>>(
+      self, other: T,
+    ) -> RangeContains<Self, T::Expression> {
+      RangeContains { left: self, right: other.as_expression() }
+    }
+  }
+
+  impl<T> DateTimeRangeExpressionMethods for T
+  where
+    T: Expression,
+    T::SqlType: RangeLike,
+  {
+  }
+
+  macro_rules! range_operators {
+    ($($(#[$attr:meta])* $name:ident => $operator:literal,)*) => {$(
+      $(#[$attr])*
+      #[derive(Clone, Copy, Debug, QueryId, ValidGrouping)]
+      pub struct $name<L, R> {
+        left: L,
+        right: R,
+      }
+
+      impl<L, R> Expression for $name<L, R>
+      where
+        L: Expression,
+        L::SqlType: RangeLike,
+        R: Expression<SqlType = L::SqlType>,
+      {
+        type SqlType = <L::SqlType as RangeLike>::Bool;
+      }
+
+      impl<L, R> QueryFragment<Pg> for $name<L, R>
+      where
+        L: QueryFragment<Pg>,
+        R: QueryFragment<Pg>,
+      {
+        fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+          out.push_sql("(");
+          self.left.walk_ast(out.reborrow())?;
+          out.push_sql(concat!(" ", $operator, " "));
+          self.right.walk_ast(out.reborrow())?;
+          out.push_sql(")");
+          Ok(())
+        }
+      }
+
+      impl<L, R, QS> AppearsOnTable<QS> for $name<L, R>
+      where
+        L: AppearsOnTable<QS>,
+        R: AppearsOnTable<QS>,
+        Self: Expression,
+      {
+      }
+
+      impl<L, R, QS> SelectableExpression<QS> for $name<L, R>
+      where
+        L: SelectableExpression<QS>,
+        R: SelectableExpression<QS>,
+        Self: AppearsOnTable<QS>,
+      {
+      }
+    )*};
+  }
+
+  range_operators! {
+    /// Whether two ranges overlap; see [`DateTimeRangeExpressionMethods::range_overlaps`].
+    RangeOverlaps => "&&",
+    /// Whether one range contains another; see
+    /// [`DateTimeRangeExpressionMethods::range_contains`].
+    RangeContains => "@>",
   }
 }
 
-const PG_EPOCH: DateTime = datetime! { 2000-01-01 00:00:00 };
+#[cfg(test)]
+mod tests {
+  use std::error::Error;
+  use std::num::NonZeroU32;
+
+  use assert2::check;
+  use diesel::PgConnection;
+  use diesel::debug_query;
+  use diesel::pg::PgMetadataLookup;
+  use diesel::pg::PgTypeMetadata;
+  use diesel::prelude::*;
+  use diesel::query_builder::BindCollector;
+  use diesel::query_builder::QueryFragment;
+  use diesel::query_builder::bind_collector::RawBytesBindCollector;
+  use diesel::query_dsl::LoadQuery;
+  use diesel::sql_types::HasSqlType;
+
+  use super::dsl::*;
+  use super::*;
+  use crate::pg::PG_EPOCH;
+  use crate::time_interval;
+
+  /// Built-in types have static OIDs, so encoding them never needs to look anything up.
+  struct NoLookup;
+
+  impl PgMetadataLookup for NoLookup {
+    fn lookup_type(&mut self, type_name: &str, _: Option<&str>) -> PgTypeMetadata {
+      unreachable!("{type_name} is not a built-in type")
+    }
+  }
+
+  /// Encode a value with its `ToSql` implementation, as Diesel does before sending it to Postgres.
+  fn encode<T, U: ToSql<T, Pg>>(value: &U) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>
+  where
+    Pg: HasSqlType<T>, {
+    let mut collector = RawBytesBindCollector::<Pg>::default();
+    collector.push_bound_value::<T, U>(value, &mut NoLookup as &mut dyn PgMetadataLookup)?;
+    Ok(collector.binds.remove(0).expect("value should not be null"))
+  }
+
+  fn decode<T, U: FromSql<T, Pg>>(bytes: &[u8]) -> DeserializeResult<U> {
+    // The OID is only consulted for arrays, records, and ranges.
+    let oid = NonZeroU32::MIN;
+    U::from_sql(PgValue::new(bytes, &oid))
+  }
+
+  #[test]
+  fn test_timestamp() -> DeserializeResult<()> {
+    for dt in [
+      datetime! { 2012-04-21 15:00:00 },
+      datetime! { 2000-01-01 00:00:00 },
+      datetime! { 1969-12-31 23:59:59.999999 },
+      datetime! { 1999-12-31 23:59:59.5 },
+    ] {
+      let bytes = encode::<sql_types::Timestamp, _>(&dt)?;
+      check!(decode::<sql_types::Timestamp, DateTime>(&bytes)? == dt);
+      let bytes = encode::<sql_types::Timestamptz, _>(&dt)?;
+      check!(decode::<sql_types::Timestamptz, DateTime>(&bytes)? == dt);
+    }
+    let bytes = encode::<sql_types::Timestamp, _>(&datetime! { 2000-01-01 00:00:01 })?;
+    check!(bytes == 1_000_000_i64.to_be_bytes());
+    Ok(())
+  }
+
+  #[test]
+  fn test_timestamp_precision_loss() {
+    use PrecisionLoss::*;
+    let micros = |dt: DateTime, policy| dt.pg_timestamp(policy);
+    let dt = datetime! { 2000-01-01 00:00:00.0000025 };
+    check!(micros(dt, Truncate) == Ok(2));
+    check!(micros(dt, RoundHalfEven) == Ok(2));
+    check!(micros(dt, Error).is_err());
+    let dt = datetime! { 2000-01-01 00:00:00.0000035 };
+    check!(micros(dt, RoundHalfEven) == Ok(4));
+    let dt = datetime! { 1999-12-31 23:59:59.9999995 };
+    check!(micros(dt, Truncate) == Ok(-1));
+    check!(micros(dt, RoundHalfEven) == Ok(0));
+    check!(micros(datetime! { 2000-01-01 00:00:00.000001 }, Error) == Ok(1));
+
+    // Rounding up past the supported range stores infinity.
+    let dt = DateTime::MAX - TimeInterval::new(0, 400);
+    check!(micros(dt, Truncate) == Ok((DateTime::MAX - PG_EPOCH).as_microseconds()));
+    check!(micros(dt, RoundHalfEven) == Ok(i64::MAX));
+  }
+
+  #[test]
+  fn test_timestamp_policy() -> DeserializeResult<()> {
+    let dt = datetime! { 2000-01-01 00:00:00.0000035 };
+    check!(encode::<sql_types::Timestamp, _>(&dt)? == 3_i64.to_be_bytes());
+    check!(encode::<sql_types::Timestamp, _>(&Rounded(dt))? == 4_i64.to_be_bytes());
+    check!(encode::<sql_types::Timestamptz, _>(&Rounded(dt))? == 4_i64.to_be_bytes());
+    check!(encode::<sql_types::Timestamp, _>(&Strict(dt)).is_err());
+    let dt = datetime! { 2000-01-01 00:00:00.000004 };
+    check!(encode::<sql_types::Timestamptz, _>(&Strict(dt))? == 4_i64.to_be_bytes());
+    Ok(())
+  }
+
+  #[test]
+  fn test_timestamp_infinity() -> DeserializeResult<()> {
+    check!(encode::<sql_types::Timestamp, _>(&DateTime::MAX)? == i64::MAX.to_be_bytes());
+    check!(encode::<sql_types::Timestamptz, _>(&DateTime::MIN)? == i64::MIN.to_be_bytes());
+    let far_future = DateTime::from_timestamp(i64::MAX, 0);
+    check!(encode::<sql_types::Timestamp, _>(&far_future)? == i64::MAX.to_be_bytes());
+
+    check!(decode::<sql_types::Timestamp, DateTime>(&i64::MAX.to_be_bytes())? == DateTime::MAX);
+    check!(decode::<sql_types::Timestamptz, DateTime>(&i64::MIN.to_be_bytes())? == DateTime::MIN);
+
+    // Finite timestamps beyond the supported range are an error rather than a sentinel.
+    let micros = (DateTime::MAX - PG_EPOCH).as_microseconds() + 1;
+    check!(decode::<sql_types::Timestamp, DateTime>(&micros.to_be_bytes()).is_err());
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_timestamptz_utc() -> DeserializeResult<()> {
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    let bytes = encode::<sql_types::Timestamptz, _>(&dt)?;
+    let loaded: DateTime = decode::<sql_types::Timestamptz, _>(&bytes)?;
+    check!(loaded == dt);
+    check!(loaded.tz_name() == Some("UTC"));
+    check!(loaded.hour() == 15);
+
+    // Timestamps without a time zone are left unspecified.
+    let loaded: DateTime = decode::<sql_types::Timestamp, _>(&bytes)?;
+    check!(loaded.tz_name() == None);
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_timestamptz_in_zone() -> DeserializeResult<()> {
+    struct Tokyo;
+    impl Zone for Tokyo {
+      const TZ: crate::tz::TimeZoneRef<'static> = crate::tz::asia::TOKYO;
+    }
+
+    let dt = datetime! { 2012-04-21 15:00:00 };
+    let bytes = encode::<sql_types::Timestamptz, _>(&dt)?;
+    let InZone(loaded, _) = decode::<sql_types::Timestamptz, InZone<Tokyo>>(&bytes)?;
+    check!(loaded == dt);
+    check!(loaded.tz_name() == Some("Asia/Tokyo"));
+    check!((loaded.day(), loaded.hour()) == (22, 0));
+
+    let InZone(loaded, _) =
+      decode::<sql_types::Timestamptz, InZone<Tokyo>>(&i64::MAX.to_be_bytes())?;
+    check!(loaded == DateTime::MAX);
+    check!(loaded.year() == 32767);
+    Ok(())
+  }
+
+  mod schema {
+    diesel::table! {
+      events (id) {
+        id -> Integer,
+        starts_at -> Timestamptz,
+        ends_at -> Nullable<Timestamptz>,
+        local_at -> Timestamp,
+        booked -> Tstzrange,
+        local_booked -> Nullable<Tsrange>,
+      }
+    }
+  }
+
+  /// The SQL for `query`, which must load as `U`.
+  fn sql<U, Q>(query: Q) -> String
+  where
+    Q: QueryFragment<Pg> + LoadQuery<'static, PgConnection, U>, {
+    debug_query::<Pg, _>(&query).to_string()
+  }
+
+  #[test]
+  fn test_dsl_functions() {
+    use schema::events::dsl::*;
+
+    check!(
+      sql::<DateTime, _>(events.select(date_trunc(Unit::Hour, starts_at)))
+        == r#"SELECT date_trunc($1, "events"."starts_at") FROM "events" -- binds: [Hour]"#
+    );
+    check!(
+      sql::<Option<DateTime>, _>(events.select(date_trunc(Unit::Day, ends_at)))
+        == r#"SELECT date_trunc($1, "events"."ends_at") FROM "events" -- binds: [Day]"#
+    );
+    check!(
+      sql::<f64, _>(events.select(extract(Field::DayOfWeek, local_at)))
+        == r#"SELECT date_part($1, "events"."local_at") FROM "events" -- binds: [DayOfWeek]"#
+    );
+    check!(
+      sql::<Option<f64>, _>(events.select(extract(Field::Epoch, ends_at)))
+        == r#"SELECT date_part($1, "events"."ends_at") FROM "events" -- binds: [Epoch]"#
+    );
+    check!(sql::<DateTime, _>(diesel::select(now())) == "SELECT now() -- binds: []");
+    check!(Unit::Millennium.as_str() == "millennium");
+    check!(Field::TimezoneHour.as_str() == "timezone_hour");
+  }
+
+  #[test]
+  fn test_dsl_operators() {
+    use schema::events::dsl::*;
+
+    check!(
+      sql::<TimeInterval, _>(events.select(local_at.since(starts_at.at_time_zone("UTC"))))
+        == concat!(
+          r#"SELECT ("events"."local_at" - ("events"."starts_at" AT TIME ZONE $1)) "#,
+          r#"FROM "events" -- binds: ["UTC"]"#,
+        )
+    );
+    check!(
+      sql::<Option<TimeInterval>, _>(events.select(ends_at.since(ends_at)))
+        == r#"SELECT ("events"."ends_at" - "events"."ends_at") FROM "events" -- binds: []"#
+    );
+    check!(
+      sql::<DateTime, _>(events.select(starts_at + time_interval!(90m)))
+        == concat!(
+          r#"SELECT ("events"."starts_at" + $1) FROM "events" "#,
+          "-- binds: [TimeInterval { seconds: 5400, nanos: 0 }]",
+        )
+    );
+    check!(
+      sql::<DateTime, _>(events.select(local_at - time_interval!(1d)))
+        == concat!(
+          r#"SELECT ("events"."local_at" - $1) FROM "events" "#,
+          "-- binds: [TimeInterval { seconds: 86400, nanos: 0 }]",
+        )
+    );
+    check!(
+      sql::<i32, _>(events.select(id).filter(starts_at.since(now()).gt(time_interval!(1h))))
+        == concat!(
+          r#"SELECT "events"."id" FROM "events" WHERE (("events"."starts_at" - now()) > $1) "#,
+          "-- binds: [TimeInterval { seconds: 3600, nanos: 0 }]",
+        )
+    );
+  }
+
+  #[test]
+  fn test_range() -> DeserializeResult<()> {
+    let start = datetime! { 2000-01-01 00:00:01 };
+    let end = datetime! { 2000-01-01 00:00:02 };
+    for range in [
+      DateTimeRange::from(start..end),
+      DateTimeRange::from(start..=end),
+      DateTimeRange::new(Bound::Excluded(start), Bound::Unbounded),
+      DateTimeRange::from(..end),
+      DateTimeRange::from(..),
+      DateTimeRange::from(DateTime::MIN..DateTime::MAX),
+      DateTimeRange::EMPTY,
+    ] {
+      let bytes = encode::<sql_types::Tstzrange, _>(&range)?;
+      check!(decode::<sql_types::Tstzrange, DateTimeRange>(&bytes)? == range);
+      let bytes = encode::<sql_types::Tsrange, _>(&range)?;
+      check!(decode::<sql_types::Tsrange, DateTimeRange>(&bytes)? == range);
+    }
+
+    // A flags byte, then each finite bound as a length-prefixed timestamp.
+    let bytes = encode::<sql_types::Tstzrange, _>(&DateTimeRange::from(start..end))?;
+    check!(
+      bytes
+        == [
+          &[0x02, 0, 0, 0, 8][..],
+          &1_000_000_i64.to_be_bytes(),
+          &[0, 0, 0, 8],
+          &2_000_000_i64.to_be_bytes()
+        ]
+        .concat()
+    );
+    let bytes = encode::<sql_types::Tstzrange, _>(&DateTimeRange::from(..=end))?;
+    check!(bytes == [&[0x0c, 0, 0, 0, 8][..], &2_000_000_i64.to_be_bytes()].concat());
+    check!(encode::<sql_types::Tstzrange, _>(&DateTimeRange::from(..))? == [0x18]);
+    check!(encode::<sql_types::Tstzrange, _>(&DateTimeRange::from(end..start))? == [0x01]);
+    let bytes = encode::<sql_types::Tstzrange, _>(&DateTimeRange::from(DateTime::MIN..))?;
+    check!(bytes == [&[0x12, 0, 0, 0, 8][..], &i64::MIN.to_be_bytes()].concat());
+    Ok(())
+  }
+
+  #[test]
+  fn test_range_malformed() {
+    let decode = |bytes: &[u8]| decode::<sql_types::Tstzrange, DateTimeRange>(bytes);
+    check!(decode(&[]).is_err());
+    check!(decode(&[0x18, 0xff]).is_err());
+    check!(decode(&[0x08]).is_err());
+    check!(decode(&[0x08, 0, 0, 0, 4, 0, 0, 0, 0]).is_err());
+    check!(decode(&[0x08, 0, 0, 0, 8, 0, 0, 0, 0]).is_err());
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_range_utc() -> DeserializeResult<()> {
+    let range = DateTimeRange::from(datetime! { 2012-04-21 11:00:00 us::EASTERN }..);
+    let bytes = encode::<sql_types::Tstzrange, _>(&range)?;
+    let Bound::Included(start) = decode::<sql_types::Tstzrange, DateTimeRange>(&bytes)?.start()
+    else {
+      panic!("start should be inclusive");
+    };
+    check!(start.tz_name() == Some("UTC"));
+    check!(start.hour() == 15);
+    let Bound::Included(start) = decode::<sql_types::Tsrange, DateTimeRange>(&bytes)?.start()
+    else {
+      panic!("start should be inclusive");
+    };
+    check!(start.tz_name() == None);
+    Ok(())
+  }
+
+  #[test]
+  fn test_dsl_ranges() {
+    use schema::events::dsl::*;
+
+    let range = DateTimeRange::from(datetime! { 2012-04-21 09:00:00 }..);
+    check!(
+      sql::<i32, _>(events.select(id).filter(booked.contains(datetime! { 2012-04-21 12:00:00 })))
+        == concat!(
+          r#"SELECT "events"."id" FROM "events" WHERE ("events"."booked" @> $1) "#,
+          "-- binds: [2012-04-21 12:00:00]",
+        )
+    );
+    check!(
+      sql::<bool, _>(events.select(booked.range_overlaps(range)))
+        == concat!(
+          r#"SELECT ("events"."booked" && $1) FROM "events" "#,
+          "-- binds: [DateTimeRange { start: Included(2012-04-21 09:00:00), end: Unbounded }]",
+        )
+    );
+    check!(
+      sql::<Option<bool>, _>(events.select(local_booked.range_contains(range)))
+        == concat!(
+          r#"SELECT ("events"."local_booked" @> $1) FROM "events" "#,
+          "-- binds: [DateTimeRange { start: Included(2012-04-21 09:00:00), end: Unbounded }]",
+        )
+    );
+    check!(
+      sql::<DateTimeRange, _>(
+        events.select(booked).filter(booked.range_overlaps(booked).and(id.eq(1)))
+      )
+      .starts_with(r#"SELECT "events"."booked" FROM "events" WHERE (("events"."booked" && "#)
+    );
+  }
+
+  #[test]
+  fn test_interval() -> DeserializeResult<()> {
+    for interval in [
+      time_interval!(0s),
+      time_interval!(1h 30m),
+      time_interval!(-1h 30m),
+      time_interval!(10d 0.000001s),
+      TimeInterval::from_microseconds(-1),
+      TimeInterval::from_microseconds(i64::MAX),
+      TimeInterval::from_microseconds(i64::MIN),
+    ] {
+      let bytes = encode::<sql_types::Interval, _>(&interval)?;
+      check!(decode::<sql_types::Interval, TimeInterval>(&bytes)? == interval);
+    }
+
+    // Intervals are written as microseconds only, truncating nanoseconds.
+    let bytes = encode::<sql_types::Interval, _>(&TimeInterval::new(90_061, 123_456_789))?;
+    let pg: PgInterval = decode::<sql_types::Interval, _>(&bytes)?;
+    check!(pg == PgInterval { microseconds: 90_061_123_456, days: 0, months: 0 });
+    let bytes = encode::<sql_types::Interval, _>(&TimeInterval::new(-1, 999_998_500))?;
+    let pg: PgInterval = decode::<sql_types::Interval, _>(&bytes)?;
+    check!(pg == PgInterval { microseconds: -1, days: 0, months: 0 });
+    check!(encode::<sql_types::Interval, _>(&TimeInterval::new(i64::MAX, 0)).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_interval_days_and_months() -> DeserializeResult<()> {
+    let read = |pg: PgInterval| {
+      let bytes = encode::<sql_types::Interval, _>(&pg).expect("intervals always encode");
+      decode::<sql_types::Interval, TimeInterval>(&bytes)
+    };
+    check!(read(PgInterval::new(0, 1, 0))? == time_interval!(1d));
+    check!(read(PgInterval::new(3_600_000_000, -1, 0))? == time_interval!(-23h));
+    check!(read(PgInterval::new(-500_000, 2, 0))? == time_interval!(1d 23h 59m 59.5s));
+    check!(read(PgInterval::new(0, 0, 1)).is_err());
+    check!(read(PgInterval::new(0, 30, -1)).is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,123 @@
+//! Serialization to/from SQLite
+//!
+//! Values follow [SQLite's storage conventions](crate::sqlite), and are always written as UTC
+//! TEXT.
+
+use diesel::deserialize::FromSql;
+use diesel::deserialize::Result as DeserializeResult;
+use diesel::serialize::IsNull;
+use diesel::serialize::Output;
+use diesel::serialize::Result as SerializeResult;
+use diesel::serialize::ToSql;
+use diesel::sql_types;
+use diesel::sqlite::Sqlite;
+use diesel::sqlite::SqliteType;
+use diesel::sqlite::SqliteValue;
+
+use crate::DateTime;
+
+impl ToSql<sql_types::Timestamp, Sqlite> for DateTime {
+  fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Sqlite>) -> SerializeResult {
+    out.set_value(self.sqlite_text());
+    Ok(IsNull::No)
+  }
+}
+
+impl ToSql<sql_types::TimestamptzSqlite, Sqlite> for DateTime {
+  fn to_sql<'se>(&'se self, out: &mut Output<'se, '_, Sqlite>) -> SerializeResult {
+    // Use `+00:00` rather than `Z`, so that the text still sorts chronologically: `+` sorts
+    // before the `.` of any fractional seconds, but `Z` sorts after it.
+    out.set_value(self.sqlite_text() + "+00:00");
+    Ok(IsNull::No)
+  }
+}
+
+impl FromSql<sql_types::Timestamp, Sqlite> for DateTime {
+  fn from_sql(mut value: SqliteValue<'_, '_, '_>) -> DeserializeResult<Self> {
+    match value.value_type() {
+      Some(SqliteType::Double) => Ok(DateTime::from_julian_day(value.read_double())?),
+      Some(SqliteType::Long) => Ok(DateTime::from_unix_time(value.read_long())?),
+      _ => Ok(value.read_text().parse()?),
+    }
+  }
+}
+
+impl FromSql<sql_types::TimestamptzSqlite, Sqlite> for DateTime {
+  fn from_sql(value: SqliteValue<'_, '_, '_>) -> DeserializeResult<Self> {
+    FromSql::<sql_types::Timestamp, Sqlite>::from_sql(value)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::error::Error;
+
+  use assert2::check;
+  use diesel::prelude::*;
+  use diesel::sql_query;
+  use diesel::sql_types::Text;
+  use diesel::sql_types::Timestamp;
+  use diesel::sql_types::TimestamptzSqlite;
+
+  use super::*;
+
+  #[derive(QueryableByName)]
+  struct Row {
+    #[diesel(sql_type = Timestamp)]
+    dt: DateTime,
+  }
+
+  #[derive(QueryableByName)]
+  struct TextRow {
+    #[diesel(sql_type = Text)]
+    text: String,
+  }
+
+  #[test]
+  fn test_storage_classes() -> Result<(), Box<dyn Error>> {
+    let conn = &mut SqliteConnection::establish(":memory:")?;
+    let mut select = |expr| sql_query(format!("SELECT {expr} AS dt")).get_result::<Row>(conn);
+    check!(select("'2012-04-21 15:00:00'")?.dt == datetime! { 2012-04-21 15:00:00 });
+    check!(select("2456039.125")?.dt == datetime! { 2012-04-21 15:00:00 });
+    check!(select("1335020400")?.dt == datetime! { 2012-04-21 15:00:00 });
+    check!(select("x'00'").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_round_trip() -> Result<(), Box<dyn Error>> {
+    let conn = &mut SqliteConnection::establish(":memory:")?;
+    let dt = datetime! { 1969-12-31 23:59:59.123456789 };
+    let row = sql_query("SELECT ? AS dt").bind::<Timestamp, _>(dt).get_result::<Row>(conn)?;
+    check!(row.dt == dt);
+    let row =
+      sql_query("SELECT ? AS dt").bind::<TimestamptzSqlite, _>(dt).get_result::<Row>(conn)?;
+    check!(row.dt == dt);
+
+    let row = sql_query("SELECT datetime(?, '+1 day') AS text")
+      .bind::<Timestamp, _>(datetime! { 2012-04-21 15:00:00.5 })
+      .get_result::<TextRow>(conn)?;
+    check!(row.text == "2012-04-22 15:00:00");
+    let mut text = |dt| -> QueryResult<String> {
+      let row = sql_query("SELECT ? AS text").bind::<TimestamptzSqlite, _>(dt);
+      Ok(row.get_result::<TextRow>(conn)?.text)
+    };
+    check!(text(datetime! { 2012-04-21 15:00:00 })? == "2012-04-21 15:00:00+00:00");
+    check!(text(datetime! { 2012-04-21 15:00:00 })? < text(datetime! { 2012-04-21 15:00:00.5 })?);
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_tz() -> Result<(), Box<dyn Error>> {
+    let conn = &mut SqliteConnection::establish(":memory:")?;
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    let row = sql_query("SELECT ? AS text")
+      .bind::<TimestamptzSqlite, _>(dt)
+      .get_result::<TextRow>(conn)?;
+    check!(row.text == "2012-04-21 15:00:00+00:00");
+    let row = sql_query("SELECT ? AS dt").bind::<Timestamp, _>(dt).get_result::<Row>(conn)?;
+    check!(row.dt == dt);
+    Ok(())
+  }
+}
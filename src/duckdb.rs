@@ -1,4 +1,8 @@
 //! Integration with DuckDB.
+//!
+//! Nanoseconds are sent to DuckDB as-is, so `TIMESTAMP_NS` columns keep them and DuckDB truncates
+//! them when storing into a `TIMESTAMP` column, which holds microseconds. Wrap values in
+//! [`Rounded`] or [`Strict`] to round or refuse them instead.
 
 use duckdb::Result;
 use duckdb::types::FromSql;
@@ -11,6 +15,9 @@ use duckdb::types::ValueRef;
 
 use crate::DateTime;
 use crate::Precision;
+use crate::Rounded;
+use crate::Strict;
+use crate::storage::Stored;
 
 macro_rules! from_sql {
   ($($precision:ident($var:ident) => $e:expr)*) => {
@@ -35,23 +42,41 @@ from_sql! {
 
 impl ToSql for DateTime {
   fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
-    match self.precision() {
-      Precision::Second =>
-        Ok(ToSqlOutput::Borrowed(ValueRef::Timestamp(TimeUnit::Second, self.as_seconds()))),
-      Precision::Millisecond => Ok(ToSqlOutput::Borrowed(ValueRef::Timestamp(
-        TimeUnit::Millisecond,
-        self.as_milliseconds(),
-      ))),
-      Precision::Microsecond => Ok(ToSqlOutput::Borrowed(ValueRef::Timestamp(
-        TimeUnit::Microsecond,
-        self.as_microseconds(),
-      ))),
-      Precision::Nanosecond => Ok(ToSqlOutput::Borrowed(ValueRef::Timestamp(
+    self.duckdb_value()
+  }
+}
+
+/// Implement `ToSql` for the wrappers that reduce a date and time to the microseconds that
+/// DuckDB's `TIMESTAMP` stores, following their precision-loss policy.
+macro_rules! to_sql {
+  ($($ty:ty),*) => {$(
+    impl ToSql for $ty {
+      fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        let (dt, policy) = self.stored();
+        dt.to_storage_precision(Precision::Microsecond, policy)
+          .map_err(|e| duckdb::Error::ToSqlConversionFailure(e.into()))?
+          .duckdb_value()
+      }
+    }
+  )*};
+}
+
+to_sql!(Rounded, Strict);
+
+impl DateTime {
+  /// Convert this date and time to a DuckDB timestamp in the coarsest unit that holds it exactly.
+  fn duckdb_value(&self) -> Result<ToSqlOutput<'static>> {
+    let (unit, value) = match self.precision() {
+      Precision::Second => (TimeUnit::Second, self.as_seconds()),
+      Precision::Millisecond => (TimeUnit::Millisecond, self.as_milliseconds()),
+      Precision::Microsecond => (TimeUnit::Microsecond, self.as_microseconds()),
+      Precision::Nanosecond => (
         TimeUnit::Nanosecond,
         (self.as_nanoseconds().try_into())
           .map_err(|e| duckdb::Error::ToSqlConversionFailure(Box::new(e)))?,
-      ))),
-    }
+      ),
+    };
+    Ok(ToSqlOutput::Borrowed(ValueRef::Timestamp(unit, value)))
   }
 }
 
@@ -77,6 +102,23 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_to_sql_precision_loss() {
+    fn timestamp(value: &dyn ToSql) -> Option<(TimeUnit, i64)> {
+      match value.to_sql() {
+        Ok(ToSqlOutput::Borrowed(ValueRef::Timestamp(unit, value))) => Some((unit, value)),
+        _ => None,
+      }
+    }
+    let dt = datetime! { 2012-04-21 15:00:00.0000015 };
+    let micros = 1335020400 * 1_000_000;
+    check!(timestamp(&dt) == Some((TimeUnit::Nanosecond, micros * 1_000 + 1_500)));
+    check!(timestamp(&Rounded(dt)) == Some((TimeUnit::Microsecond, micros + 2)));
+    check!(Strict(dt).to_sql().is_err());
+    let dt = datetime! { 2012-04-21 15:00:00.000002 };
+    check!(timestamp(&Strict(dt)) == Some((TimeUnit::Microsecond, micros + 2)));
+  }
+
   #[test]
   fn test_to_sql() -> Result<()> {
     let dt = datetime! { 2012-04-21 15:00:00 };
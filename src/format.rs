@@ -1,23 +1,26 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::fmt::Display;
-use std::fmt::Error;
 use std::fmt::Formatter;
 use std::fmt::Result;
 use std::fmt::Write;
+use std::str::CharIndices;
 
 use crate::DateTime;
 
 /// A date with a requested format.
 pub struct FormattedDateTime<'a> {
   pub(crate) dt: &'a DateTime,
-  pub(crate) format: &'a str,
+  pub(crate) format: Cow<'a, str>,
 }
 
 impl FormattedDateTime<'_> {
   fn tz_offset(&self) -> String {
     #[cfg(feature = "tz")]
     match self.dt.tz {
-      crate::tz::TimeZone::Tz(_) | crate::tz::TimeZone::FixedOffset(_) =>
+      crate::tz::TimeZone::Tz(_)
+      | crate::tz::TimeZone::Named(..)
+      | crate::tz::TimeZone::FixedOffset(_) =>
         format!("{:+03}{:02}", self.dt.tz_offset() / 3600, self.dt.tz_offset() % 3600 / 60,),
       crate::tz::TimeZone::Unspecified => String::new(),
     }
@@ -36,101 +39,207 @@ impl Display for FormattedDateTime<'_> {
   fn fmt(&self, f: &mut Formatter<'_>) -> Result {
     // Iterate over the format string and consume it.
     let dt = self.dt;
-    let mut flag = false;
+    let mut items = Items::new(&self.format);
+    loop {
+      let start = items.chars.offset();
+      let Some(item) = items.next() else { break };
+      let (c, padding, prefix, div) = match item {
+        Ok(Item::Literal(c)) => {
+          f.write_char(c)?;
+          continue;
+        },
+        Ok(Item::Spec { spec, padding, prefix, div }) => (spec, padding, prefix, div),
+        // Like `strftime`, write out anything that is not a valid specifier verbatim.
+        Err(_) => {
+          f.write_str(&self.format[start..items.chars.offset()])?;
+          continue;
+        },
+      };
+
+      // Set up a macro to process padding.
+      macro_rules! write_padded {
+        ($f:ident, $pad:ident, $level:literal, $e:expr) => {
+          match $pad {
+            Padding::Default | Padding::Zero => write!($f, concat!("{:0", $level, "}"), $e),
+            Padding::Space => write!($f, concat!("{:", $level, "}"), $e),
+            Padding::Suppress => write!($f, "{}", $e),
+          }
+        };
+      }
+
+      // Write out the formatted component.
+      match c {
+        'Y' => write_padded!(f, padding, 4, dt.year())?,
+        'C' => write_padded!(f, padding, 2, dt.year() / 100)?,
+        'y' => write_padded!(f, padding, 2, dt.year() % 100)?,
+        'm' => write_padded!(f, padding, 2, dt.month())?,
+        'b' | 'h' => write!(f, "{}", dt.month_abbv())?,
+        'B' => write!(f, "{}", dt.month_name())?,
+        'd' => write_padded!(f, padding, 2, dt.day())?,
+        'a' => write!(f, "{}", dt.weekday().to_string().chars().take(3).collect::<String>())?,
+        'A' => write!(f, "{}", dt.weekday())?,
+        'w' => write!(f, "{}", dt.weekday() as u8)?,
+        'u' => write!(f, "{}", match dt.weekday() {
+          crate::Weekday::Sunday => 7,
+          _ => self.dt.weekday() as u8,
+        })?,
+        // U, W
+        'j' => write_padded!(f, padding, 3, dt.day_of_year())?,
+        'H' => write_padded!(f, padding, 2, dt.hour())?,
+        'I' => write_padded!(f, padding, 2, match dt.hour() {
+          0 => 12,
+          1..=12 => dt.hour(),
+          13.. => dt.hour() - 12,
+        })?,
+        'M' => write_padded!(f, padding, 2, dt.minute())?,
+        'S' => write_padded!(f, padding, 2, dt.second())?,
+        'z' => write!(f, "{}", self.tz_offset())?,
+        #[cfg(feature = "tz")]
+        'Q' => write!(f, "{}", dt.tz_name().unwrap_or_default())?,
+        #[cfg(not(feature = "tz"))]
+        'Q' => {},
+        'P' => write!(f, "{}", if dt.hour() > 12 { "PM" } else { "AM" })?,
+        'p' => write!(f, "{}", if dt.hour() > 12 { "pm" } else { "am" })?,
+        's' => write!(f, "{}", dt.seconds)?,
+        'f' => {
+          if let Some(pre) = prefix {
+            f.write_char(pre)?;
+          }
+          match div {
+            1_000 => write!(f, "{:06}", dt.nanosecond() / div)?,
+            1_000_000 => write!(f, "{:03}", dt.nanosecond() / div)?,
+            _ => write!(f, "{:09}", dt.nanosecond())?,
+          };
+        },
+        'D' => write!(f, "{:02}/{:02}/{:02}", dt.month(), dt.day(), dt.year())?,
+        'F' => write!(f, "{:04}-{:02}-{:02}", dt.year(), dt.month(), dt.day())?,
+        'v' => write!(f, "{:2}-{}-{:04}", dt.day(), dt.month_abbv(), dt.year())?,
+        'R' => write!(f, "{:2}:{:2}", dt.hour(), dt.minute())?,
+        'T' => write!(f, "{:2}:{:2}:{:2}", dt.hour(), dt.minute(), dt.second())?,
+        't' => f.write_char('\t')?,
+        'n' => f.write_char('\n')?,
+        '%' => f.write_char('%')?,
+        _ => unreachable!("Items only yields known specifiers"),
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<'a> FormattedDateTime<'a> {
+  /// Create a formatted date and time, validating the format string up front.
+  pub(crate) fn try_new(
+    dt: &'a DateTime, format: Cow<'a, str>,
+  ) -> std::result::Result<Self, FormatError> {
+    Items::new(&format).try_for_each(|item| item.map(drop))?;
+    Ok(Self { dt, format })
+  }
+}
+
+/// A single component of a format string.
+enum Item {
+  /// A character to be written out verbatim.
+  Literal(char),
+  /// A `%`-prefixed specifier, along with its modifiers.
+  Spec { spec: char, padding: Padding, prefix: Option<char>, div: u32 },
+}
+
+/// An iterator over the components of a format string.
+struct Items<'a> {
+  format: &'a str,
+  chars: CharIndices<'a>,
+}
+
+impl<'a> Items<'a> {
+  fn new(format: &'a str) -> Self {
+    Self { format, chars: format.char_indices() }
+  }
+
+  fn err(&self, index: usize, kind: FormatErrorKind) -> FormatError {
+    FormatError { format: self.format.into(), index, kind }
+  }
+}
+
+impl Iterator for Items<'_> {
+  type Item = std::result::Result<Item, FormatError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (start, c) = self.chars.next()?;
+    if c != '%' {
+      return Some(Ok(Item::Literal(c)));
+    }
+
+    // Consume any modifiers, and then the specifier itself.
     let mut padding = Padding::Default;
     let mut prefix = None;
     let mut div = 1;
-    for c in self.format.chars() {
-      if flag {
-        // Apply padding if this is a padding change.
-        #[rustfmt::skip]
-        match c {
-          '0' => { padding = Padding::Zero; continue; },
-          '-' => { padding = Padding::Suppress; continue; },
-          '_' => { padding = Padding::Space; continue; },
-          '.' => { prefix = Some('.'); continue; },
-          '3' => { div = 1_000_000; continue; },
-          '6' => { div = 1_000; continue; },
-          '9' => { div = 1; continue; },
-          _ => {},
-        };
+    loop {
+      let Some((ix, c)) = self.chars.next() else {
+        return Some(Err(self.err(start, FormatErrorKind::Incomplete)));
+      };
+      #[rustfmt::skip]
+      match c {
+        '0' => { padding = Padding::Zero; continue; },
+        '-' => { padding = Padding::Suppress; continue; },
+        '_' => { padding = Padding::Space; continue; },
+        '.' => { prefix = Some('.'); continue; },
+        '3' => { div = 1_000_000; continue; },
+        '6' => { div = 1_000; continue; },
+        '9' => { div = 1; continue; },
+        _ => {},
+      };
+      if c != 'f' && (div != 1 || prefix.is_some()) {
+        return Some(Err(self.err(ix, FormatErrorKind::MisplacedModifier(c))));
+      }
+      return Some(match c {
+        'Y' | 'C' | 'y' | 'm' | 'b' | 'h' | 'B' | 'd' | 'a' | 'A' | 'w' | 'u' | 'j' | 'H'
+        | 'I' | 'M' | 'S' | 'z' | 'Q' | 'P' | 'p' | 's' | 'f' | 'D' | 'F' | 'v' | 'R' | 'T'
+        | 't' | 'n' | '%' => Ok(Item::Spec { spec: c, padding, prefix, div }),
+        _ => Err(self.err(ix, FormatErrorKind::UnknownSpecifier(c))),
+      });
+    }
+  }
+}
 
-        if c != 'f' && (div != 1 || prefix.is_some()) {
-          panic!("Invalid modifier; `.`, `3`, and `6` only allowed on `f` (fractional seconds).");
-        }
+/// Errors occurring while interpreting a format string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FormatError {
+  /// An owned copy of the format string.
+  pub format: String,
+  /// The index in the format string where the error occurred.
+  pub index: usize,
+  /// A machine-readable explanation of the error.
+  pub kind: FormatErrorKind,
+}
 
-        // Set up a macro to process padding.
-        macro_rules! write_padded {
-          ($f:ident, $pad:ident, $level:literal, $e:expr) => {
-            match $pad {
-              Padding::Default | Padding::Zero => write!($f, concat!("{:0", $level, "}"), $e),
-              Padding::Space => write!($f, concat!("{:", $level, "}"), $e),
-              Padding::Suppress => write!($f, "{}", $e),
-            }
-          };
-        }
+impl Display for FormatError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    write!(f, "{}\n{}^-----\n{}", self.format, " ".repeat(self.index), self.kind)
+  }
+}
 
-        // Write out the formatted component.
-        flag = false;
-        match c {
-          'Y' => write_padded!(f, padding, 4, dt.year())?,
-          'C' => write_padded!(f, padding, 2, dt.year() / 100)?,
-          'y' => write_padded!(f, padding, 2, dt.year() % 100)?,
-          'm' => write_padded!(f, padding, 2, dt.month())?,
-          'b' | 'h' => write!(f, "{}", dt.month_abbv())?,
-          'B' => write!(f, "{}", dt.month_name())?,
-          'd' => write_padded!(f, padding, 2, dt.day())?,
-          'a' => write!(f, "{}", dt.weekday().to_string().chars().take(3).collect::<String>())?,
-          'A' => write!(f, "{}", dt.weekday())?,
-          'w' => write!(f, "{}", dt.weekday() as u8)?,
-          'u' => write!(f, "{}", match dt.weekday() {
-            crate::Weekday::Sunday => 7,
-            _ => self.dt.weekday() as u8,
-          })?,
-          // U, W
-          'j' => write_padded!(f, padding, 3, dt.day_of_year())?,
-          'H' => write_padded!(f, padding, 2, dt.hour())?,
-          'I' => write_padded!(f, padding, 2, match dt.hour() {
-            0 => 12,
-            1..=12 => dt.hour(),
-            13.. => dt.hour() - 12,
-          })?,
-          'M' => write_padded!(f, padding, 2, dt.minute())?,
-          'S' => write_padded!(f, padding, 2, dt.second())?,
-          'z' => write!(f, "{}", self.tz_offset())?,
-          'P' => write!(f, "{}", if dt.hour() > 12 { "PM" } else { "AM" })?,
-          'p' => write!(f, "{}", if dt.hour() > 12 { "pm" } else { "am" })?,
-          's' => write!(f, "{}", dt.seconds)?,
-          'f' => {
-            if let Some(pre) = prefix {
-              f.write_char(pre)?;
-            }
-            match div {
-              1_000 => write!(f, "{:06}", dt.nanosecond() / div)?,
-              1_000_000 => write!(f, "{:03}", dt.nanosecond() / div)?,
-              _ => write!(f, "{:09}", dt.nanosecond())?,
-            };
-            prefix = None;
-            div = 1;
-          },
-          'D' => write!(f, "{:02}/{:02}/{:02}", dt.month(), dt.day(), dt.year())?,
-          'F' => write!(f, "{:04}-{:02}-{:02}", dt.year(), dt.month(), dt.day())?,
-          'v' => write!(f, "{:2}-{}-{:04}", dt.day(), dt.month_abbv(), dt.year())?,
-          'R' => write!(f, "{:2}:{:2}", dt.hour(), dt.minute())?,
-          'T' => write!(f, "{:2}:{:2}:{:2}", dt.hour(), dt.minute(), dt.second())?,
-          't' => f.write_char('\t')?,
-          'n' => f.write_char('\n')?,
-          '%' => f.write_char('%')?,
-          _ => Err(Error)?,
-        }
-      } else if c == '%' {
-        flag = true;
-        padding = Padding::Default;
-      } else {
-        f.write_char(c)?;
-      }
+impl std::error::Error for FormatError {}
+
+/// Potential errors that occur while interpreting a format string.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum FormatErrorKind {
+  /// The format string ended with an unterminated `%`.
+  Incomplete,
+  /// A modifier (`.`, `3`, `6`, or `9`) was applied to a specifier other than `f`.
+  MisplacedModifier(char),
+  /// The specifier is not recognized.
+  UnknownSpecifier(char),
+}
+
+impl Display for FormatErrorKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    match self {
+      Self::Incomplete => write!(f, "Format string ends with an incomplete specifier"),
+      Self::MisplacedModifier(c) =>
+        write!(f, "Modifiers `.`, `3`, `6`, and `9` are only allowed on `f`, not `{c}`"),
+      Self::UnknownSpecifier(c) => write!(f, "Unknown format specifier: `%{c}`"),
     }
-    Ok(())
   }
 }
 
@@ -179,7 +288,8 @@ month_str! {
 }
 
 /// A padding modifier
-enum Padding {
+#[derive(Clone, Copy)]
+pub(crate) enum Padding {
   /// Use the default padding (usually either `0` or nothing).
   Default,
   /// Explicitly pad with `0`
@@ -222,6 +332,27 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_format_invalid() {
+    let date = datetime! { 2012-04-21 11:00:00 };
+    check!(date.format("%K").to_string() == "%K");
+    check!(date.format("%Y-%K-%d").to_string() == "2012-%K-21");
+    check!(date.format("%Y %.3d").to_string() == "2012 %.3d");
+    check!(date.format("%Y %").to_string() == "2012 %");
+    check!(date.try_format("%K").is_err());
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_format_tz() {
+    let dt = datetime! { 2024-07-04 15:30:45 america::NEW_YORK };
+    check!(
+      dt.format("%Y-%m-%dT%H:%M:%S%z[%Q]").to_string()
+        == "2024-07-04T15:30:45-0400[America/New_York]"
+    );
+    check!(datetime! { 2024-07-04 15:30:45 }.format("%Q").to_string() == "");
+  }
+
   #[test]
   fn test_padding() {
     let date = datetime! { 2024-07-04 17:30:00 };
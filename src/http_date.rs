@@ -0,0 +1,239 @@
+//! HTTP dates (RFC 9110 § 5.6.7), as used in `Date`, `Last-Modified`, and similar headers.
+
+use crate::DateTime;
+use crate::ParseErrorKind;
+use crate::ParseResult;
+use crate::format::Padding;
+use crate::parse::Input;
+use crate::parse::MONTHS;
+use crate::parse::WEEKDAYS;
+use crate::parse::days_in_month;
+
+impl DateTime {
+  /// Parse an HTTP date in any of the three formats that RFC 9110 requires recipients to accept:
+  ///
+  /// - IMF-fixdate (preferred): `Sun, 06 Nov 1994 08:49:37 GMT`
+  /// - RFC 850 (obsolete): `Sunday, 06-Nov-94 08:49:37 GMT`
+  /// - ANSI C `asctime()` (obsolete): `Sun Nov  6 08:49:37 1994`
+  ///
+  /// HTTP dates are always in UTC, so the result has no time zone. A two-digit RFC 850 year that
+  /// would be more than 50 years in the future is interpreted as the most recent matching year in
+  /// the past; use [`DateTime::parse_http_date_relative_to`] to interpret it relative to a date
+  /// other than today. The day of the week is not checked against the date.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::DateTime;
+  /// use datetime::datetime;
+  ///
+  /// let dt = DateTime::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+  /// assert_eq!(dt, datetime! { 1994-11-06 08:49:37 });
+  /// ```
+  pub fn parse_http_date(s: impl AsRef<str>) -> ParseResult<Self> {
+    Self::parse_http_date_relative_to(s, DateTime::now())
+  }
+
+  /// Parse an HTTP date, as with [`DateTime::parse_http_date`], interpreting a two-digit RFC 850
+  /// year relative to the year of the given reference date rather than the current one.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::DateTime;
+  /// use datetime::datetime;
+  ///
+  /// let reference = datetime! { 2012-04-21 11:00:00 };
+  /// let dt = DateTime::parse_http_date_relative_to("Sunday, 06-Nov-94 08:49:37 GMT", reference);
+  /// assert_eq!(dt.unwrap(), datetime! { 1994-11-06 08:49:37 });
+  /// ```
+  pub fn parse_http_date_relative_to(
+    s: impl AsRef<str>, reference: DateTime,
+  ) -> ParseResult<Self> {
+    let mut input = Input::new(s.as_ref());
+    input.name(&WEEKDAYS)?;
+    let (year, month, day, hms);
+    if input.eat(',') {
+      input.expect(' ')?;
+      day = (input.pos(), input.int(2, Padding::Zero)? as u8);
+      if input.eat('-') {
+        // RFC 850: `Sunday, 06-Nov-94 08:49:37 GMT`
+        month = input.name(&MONTHS)? as u8 + 1;
+        input.expect('-')?;
+        let (year_start, this_year) = (input.pos(), reference.year() as i32);
+        let year_of_century = input.int(2, Padding::Zero)? as i32;
+        year = match this_year - this_year.rem_euclid(100) + year_of_century {
+          y if y > this_year + 50 => y - 100,
+          y if y <= this_year - 50 => y + 100,
+          y => y,
+        }
+        .try_into()
+        .map_err(|_| input.err_at(year_start, ParseErrorKind::InvalidDate))?;
+      } else {
+        // IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`
+        input.expect(' ')?;
+        month = input.name(&MONTHS)? as u8 + 1;
+        input.expect(' ')?;
+        year = input.int(4, Padding::Zero)? as i16;
+      }
+      input.expect(' ')?;
+      hms = input.hms()?;
+      input.expect(' ')?;
+      for c in "GMT".chars() {
+        input.expect(c)?;
+      }
+    } else {
+      // asctime: `Sun Nov  6 08:49:37 1994`
+      input.expect(' ')?;
+      month = input.name(&MONTHS)? as u8 + 1;
+      input.expect(' ')?;
+      day = (input.pos(), input.int(2, Padding::Space)? as u8);
+      input.expect(' ')?;
+      hms = input.hms()?;
+      input.expect(' ')?;
+      year = input.int(4, Padding::Zero)? as i16;
+    }
+    input.assert_consumed()?;
+
+    let (day_start, day) = day;
+    if day == 0 || day > days_in_month(year, month) {
+      return Err(input.err_at(day_start, ParseErrorKind::InvalidDate));
+    }
+    Ok(DateTime::ymd(year, month, day).hms(hms.0, hms.1, hms.2).build())
+  }
+
+  /// Format this date and time as an HTTP date, in the IMF-fixdate format:
+  /// `Sun, 06 Nov 1994 08:49:37 GMT`.
+  ///
+  /// The output is always in UTC, regardless of this date and time's time zone. Fractional seconds
+  /// are truncated.
+  pub fn to_http_date(&self) -> String {
+    DateTime::from_timestamp(self.seconds, 0).format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+  }
+}
+
+#[cfg(feature = "http")]
+impl From<DateTime> for http::HeaderValue {
+  fn from(dt: DateTime) -> Self {
+    Self::from(&dt)
+  }
+}
+
+#[cfg(feature = "http")]
+impl From<&DateTime> for http::HeaderValue {
+  fn from(dt: &DateTime) -> Self {
+    Self::try_from(dt.to_http_date()).expect("HTTP dates are valid header values")
+  }
+}
+
+#[cfg(feature = "http")]
+impl TryFrom<&http::HeaderValue> for DateTime {
+  type Error = crate::ParseError;
+
+  fn try_from(value: &http::HeaderValue) -> ParseResult<Self> {
+    match value.to_str() {
+      Ok(s) => DateTime::parse_http_date(s),
+      Err(_) => Err(crate::ParseError {
+        src: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        index: value.as_bytes().iter().position(|b| !b.is_ascii_graphic() && *b != b' '),
+        kind: ParseErrorKind::Unexpected,
+      }),
+    }
+  }
+}
+
+#[cfg(feature = "http")]
+impl TryFrom<http::HeaderValue> for DateTime {
+  type Error = crate::ParseError;
+
+  fn try_from(value: http::HeaderValue) -> ParseResult<Self> {
+    Self::try_from(&value)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+
+  #[test]
+  fn test_parse_http_date() -> ParseResult<()> {
+    let expected = datetime! { 1994-11-06 08:49:37 };
+    for s in [
+      "Sun, 06 Nov 1994 08:49:37 GMT",
+      "Sunday, 06-Nov-94 08:49:37 GMT",
+      "Sun Nov  6 08:49:37 1994",
+      "Sun Nov 06 08:49:37 1994",
+    ] {
+      check!(DateTime::parse_http_date(s)? == expected, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_http_date_two_digit_year() -> ParseResult<()> {
+    let reference = datetime! { 2026-10-18 00:00:00 };
+    for (s, expected) in [
+      ("Tuesday, 01-Jan-30 00:00:00 GMT", datetime! { 2030-01-01 00:00:00 }),
+      ("Sunday, 06-Nov-94 08:49:37 GMT", datetime! { 1994-11-06 08:49:37 }),
+      ("Saturday, 01-Jan-00 00:00:00 GMT", datetime! { 2000-01-01 00:00:00 }),
+    ] {
+      check!(DateTime::parse_http_date_relative_to(s, reference)? == expected, "{s}");
+    }
+    let reference = datetime! { 2090-01-01 00:00:00 };
+    let dt = DateTime::parse_http_date_relative_to("Tuesday, 01-Jan-30 00:00:00 GMT", reference)?;
+    check!(dt == datetime! { 2130-01-01 00:00:00 });
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_http_date_errors() {
+    for (s, index, kind) in [
+      ("Sun 06 Nov 1994 08:49:37 GMT", 4, ParseErrorKind::Unexpected),
+      ("Sun, 06 Nov 1994 08:49:37 UTC", 26, ParseErrorKind::Unexpected),
+      ("Sun, 06 Nov 1994 08:49:37 +0000", 26, ParseErrorKind::Unexpected),
+      ("Sun, 06 Nov 1994 08:49:37", 25, ParseErrorKind::InputTooShort),
+      ("Sun, 31 Nov 1994 08:49:37 GMT", 5, ParseErrorKind::InvalidDate),
+      ("Sun, 06 Nov 1994 25:49:37 GMT", 17, ParseErrorKind::InvalidTime),
+      ("Sun Nov  6 08:49:37 1994 GMT", 24, ParseErrorKind::InputTooLong),
+    ] {
+      let err = DateTime::parse_http_date(s).unwrap_err();
+      check!(err.index == Some(index), "{s}");
+      check!(err.kind == kind, "{s}");
+    }
+  }
+
+  #[test]
+  fn test_to_http_date() -> ParseResult<()> {
+    let dt = datetime! { 1994-11-06 08:49:37.5 };
+    check!(dt.to_http_date() == "Sun, 06 Nov 1994 08:49:37 GMT");
+    check!(DateTime::parse_http_date(dt.to_http_date())? == datetime! { 1994-11-06 08:49:37 });
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_to_http_date_tz() {
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    check!(dt.to_http_date() == "Sat, 21 Apr 2012 15:00:00 GMT");
+  }
+
+  #[cfg(feature = "http")]
+  #[test]
+  fn test_header_value() -> ParseResult<()> {
+    use http::HeaderValue;
+
+    let dt = datetime! { 1994-11-06 08:49:37 };
+    let value = HeaderValue::from(dt);
+    check!(value == "Sun, 06 Nov 1994 08:49:37 GMT");
+    check!(DateTime::try_from(&value)? == dt);
+    check!(DateTime::try_from(HeaderValue::from_static("Sun Nov  6 08:49:37 1994"))? == dt);
+
+    let value = HeaderValue::from_bytes(b"Sun, 06 Nov 1994 08:49:37 GMT\xff").unwrap();
+    let err = DateTime::try_from(value).unwrap_err();
+    check!(err.index == Some(29));
+    check!(err.kind == ParseErrorKind::Unexpected);
+    Ok(())
+  }
+}
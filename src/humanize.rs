@@ -0,0 +1,403 @@
+//! Human-friendly descriptions of intervals and relative times, such as "3 hours ago".
+//!
+//! [`TimeInterval::humanize`] and [`DateTime::humanize_since`] return a [`Humanized`] value,
+//! which can be configured and then displayed:
+//!
+//! ```
+//! use datetime::humanize::Style;
+//! use datetime::humanize::Unit;
+//! use datetime::time_interval;
+//!
+//! let interval = time_interval!(1d 3h 25m);
+//! assert_eq!(interval.humanize().to_string(), "1 day");
+//! assert_eq!(interval.humanize().units(2).to_string(), "1 day and 3 hours");
+//! assert_eq!(interval.humanize().largest(Unit::Hour).style(Style::Short).to_string(), "27h");
+//! ```
+//!
+//! The words themselves come from a [`Renderer`]; the built-in renderer is [`English`].
+
+use std::fmt;
+
+use crate::DateTime;
+use crate::interval::TimeInterval;
+
+impl TimeInterval {
+  /// Describe this interval in human-friendly terms, such as "3 hours".
+  ///
+  /// By default, this uses the single largest unit that fits, rounded down, in the long style. Use
+  /// the methods on [`Humanized`] to configure it.
+  pub const fn humanize(&self) -> Humanized<'static> {
+    Humanized::new(*self, Tense::Duration)
+  }
+}
+
+impl DateTime {
+  /// Describe this date and time relative to the given reference in human-friendly terms, such as
+  /// "3 hours ago" or "in 2 days".
+  ///
+  /// By default, this uses the single largest unit that fits, rounded down, in the long style. Use
+  /// the methods on [`Humanized`] to configure it.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::datetime;
+  ///
+  /// let dt = datetime! { 2012-04-21 08:00:00 };
+  /// assert_eq!(dt.humanize_since(&datetime! { 2012-04-21 11:30:00 }).to_string(), "3 hours ago");
+  /// assert_eq!(dt.humanize_since(&datetime! { 2012-04-19 08:00:00 }).to_string(), "in 2 days");
+  /// ```
+  pub fn humanize_since(&self, reference: &DateTime) -> Humanized<'static> {
+    let interval = *self - *reference;
+    Humanized::new(interval, match interval.seconds() < 0 {
+      true => Tense::Past,
+      false => Tense::Future,
+    })
+  }
+}
+
+/// A human-friendly description of an interval or relative time.
+///
+/// This implements [`Display`](fmt::Display); the methods configure what is displayed.
+#[derive(Clone, Copy)]
+#[must_use]
+pub struct Humanized<'a> {
+  interval: TimeInterval,
+  tense: Tense,
+  largest: Unit,
+  smallest: Unit,
+  units: usize,
+  rounding: Rounding,
+  style: Style,
+  renderer: &'a dyn Renderer,
+}
+
+impl<'a> Humanized<'a> {
+  const fn new(interval: TimeInterval, tense: Tense) -> Self {
+    Self {
+      interval,
+      tense,
+      largest: Unit::Year,
+      smallest: Unit::Second,
+      units: 1,
+      rounding: Rounding::Down,
+      style: Style::Long,
+      renderer: &English,
+    }
+  }
+
+  /// The largest unit to use (by default, [`Unit::Year`]).
+  pub const fn largest(mut self, unit: Unit) -> Self {
+    self.largest = unit;
+    self
+  }
+
+  /// The smallest unit to use (by default, [`Unit::Second`]).
+  pub const fn smallest(mut self, unit: Unit) -> Self {
+    self.smallest = unit;
+    self
+  }
+
+  /// The number of consecutive units to use (by default, one).
+  ///
+  /// Units with a count of zero are omitted, so "1 day and 0 hours" is rendered as "1 day".
+  pub const fn units(mut self, units: usize) -> Self {
+    self.units = if units == 0 { 1 } else { units };
+    self
+  }
+
+  /// How to round the final unit (by default, [`Rounding::Down`]).
+  pub const fn rounding(mut self, rounding: Rounding) -> Self {
+    self.rounding = rounding;
+    self
+  }
+
+  /// The style of the output (by default, [`Style::Long`]).
+  pub const fn style(mut self, style: Style) -> Self {
+    self.style = style;
+    self
+  }
+
+  /// The renderer that produces the output (by default, [`English`]).
+  pub fn renderer<'b>(self, renderer: &'b dyn Renderer) -> Humanized<'b> {
+    Humanized { renderer, ..self }
+  }
+
+  /// The units and counts to display, from largest to smallest.
+  ///
+  /// If the interval rounds to zero, this is a single count of zero of the smallest unit.
+  pub fn parts(&self) -> Vec<(Unit, u64)> {
+    let (largest, smallest) = match self.largest <= self.smallest {
+      true => (self.largest, self.smallest),
+      false => (self.smallest, self.largest),
+    };
+    let units = &Unit::ALL[largest as usize..=smallest as usize];
+    let mut nanos = self.interval.as_nanoseconds().unsigned_abs();
+
+    // Round to the final unit, repeating if rounding carries into a larger unit.
+    let (first, last) = loop {
+      let first = units.iter().position(|u| nanos >= u.nanos()).unwrap_or(units.len() - 1);
+      let last = (first + self.units - 1).min(units.len() - 1);
+      let size = units[last].nanos();
+      let rounded = match self.rounding {
+        Rounding::Down => nanos / size,
+        Rounding::Nearest => (nanos + size / 2) / size,
+        Rounding::Up => nanos.div_ceil(size),
+      } * size;
+      let carried = units.iter().position(|u| rounded >= u.nanos()).unwrap_or(units.len() - 1);
+      nanos = rounded;
+      if carried == first {
+        break (first, last);
+      }
+    };
+
+    let mut parts = Vec::with_capacity(last - first + 1);
+    for unit in &units[first..=last] {
+      let count = nanos / unit.nanos();
+      nanos %= unit.nanos();
+      if count > 0 {
+        parts.push((*unit, count as u64));
+      }
+    }
+    if parts.is_empty() {
+      parts.push((units[last], 0));
+    }
+    parts
+  }
+}
+
+impl fmt::Display for Humanized<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let parts = self.parts();
+    if self.tense == Tense::Duration && self.interval.seconds() < 0 && parts[0].1 > 0 {
+      f.write_str("-")?;
+    }
+    self.renderer.render(f, &parts, self.tense, self.style)
+  }
+}
+
+impl fmt::Debug for Humanized<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Humanized({self})")
+  }
+}
+
+/// A unit of time used in humanized output.
+///
+/// Months are treated as 30 days and years as 365 days.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum Unit {
+  Year,
+  Month,
+  Week,
+  Day,
+  Hour,
+  Minute,
+  Second,
+}
+
+impl Unit {
+  const ALL: [Self; 7] =
+    [Self::Year, Self::Month, Self::Week, Self::Day, Self::Hour, Self::Minute, Self::Second];
+
+  /// The length of this unit, in nanoseconds.
+  const fn nanos(self) -> u128 {
+    1_000_000_000
+      * match self {
+        Self::Year => 365 * 86_400,
+        Self::Month => 30 * 86_400,
+        Self::Week => 7 * 86_400,
+        Self::Day => 86_400,
+        Self::Hour => 3_600,
+        Self::Minute => 60,
+        Self::Second => 1,
+      }
+  }
+}
+
+/// How to round the final unit of humanized output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+  /// Round toward zero: 1 hour and 50 minutes is "1 hour".
+  Down,
+  /// Round to the nearest count, with halves rounded away from zero: 1 hour and 30 minutes is "2
+  /// hours".
+  Nearest,
+  /// Round away from zero: 1 hour and 10 minutes is "2 hours".
+  Up,
+}
+
+/// The style of humanized output.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Style {
+  /// Full words, such as "3 hours ago".
+  Long,
+  /// Abbreviations, such as "3h ago".
+  Short,
+}
+
+/// Whether humanized output describes a duration, or a time in the past or future.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tense {
+  /// A duration, such as "3 hours".
+  Duration,
+  /// A time in the past, such as "3 hours ago".
+  Past,
+  /// A time in the future, such as "in 3 hours".
+  Future,
+}
+
+/// Produces the words for humanized output, allowing for localization.
+pub trait Renderer {
+  /// Write the given units and counts (ordered from largest to smallest) in the given tense and
+  /// style.
+  ///
+  /// If the interval rounded to zero, `parts` is a single count of zero of the smallest unit.
+  /// A negative duration's minus sign has already been written.
+  fn render(
+    &self, f: &mut fmt::Formatter<'_>, parts: &[(Unit, u64)], tense: Tense, style: Style,
+  ) -> fmt::Result;
+}
+
+/// Renders humanized output in English.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct English;
+
+impl Renderer for English {
+  fn render(
+    &self, f: &mut fmt::Formatter<'_>, parts: &[(Unit, u64)], tense: Tense, style: Style,
+  ) -> fmt::Result {
+    if tense != Tense::Duration && parts[0].1 == 0 {
+      return f.write_str("now");
+    }
+    if tense == Tense::Future {
+      f.write_str("in ")?;
+    }
+    for (ix, &(unit, count)) in parts.iter().enumerate() {
+      match (style, ix) {
+        (_, 0) => {},
+        (Style::Short, _) => f.write_str(" ")?,
+        (Style::Long, ix) if ix + 1 < parts.len() => f.write_str(", ")?,
+        (Style::Long, 1) => f.write_str(" and ")?,
+        (Style::Long, _) => f.write_str(", and ")?,
+      }
+      let (singular, short) = match unit {
+        Unit::Year => ("year", "y"),
+        Unit::Month => ("month", "mo"),
+        Unit::Week => ("week", "w"),
+        Unit::Day => ("day", "d"),
+        Unit::Hour => ("hour", "h"),
+        Unit::Minute => ("minute", "m"),
+        Unit::Second => ("second", "s"),
+      };
+      match (style, count) {
+        (Style::Short, _) => write!(f, "{count}{short}")?,
+        (Style::Long, 1) => write!(f, "1 {singular}")?,
+        (Style::Long, _) => write!(f, "{count} {singular}s")?,
+      }
+    }
+    if tense == Tense::Past {
+      f.write_str(" ago")?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+  use crate::time_interval;
+
+  #[test]
+  fn test_humanize() {
+    for (interval, expected) in [
+      (time_interval!(0s), "0 seconds"),
+      (time_interval!(1s), "1 second"),
+      (time_interval!(59.9s), "59 seconds"),
+      (time_interval!(90s), "1 minute"),
+      (time_interval!(3h 59m), "3 hours"),
+      (time_interval!(1d), "1 day"),
+      (time_interval!(13d), "1 week"),
+      (time_interval!(45d), "1 month"),
+      (time_interval!(800d), "2 years"),
+      (time_interval!(-3h), "-3 hours"),
+      (time_interval!(-0.5s), "0 seconds"),
+    ] {
+      check!(interval.humanize().to_string() == expected, "{interval:?}");
+    }
+  }
+
+  #[test]
+  fn test_humanize_units() {
+    let interval = time_interval!(1d 2h 3m 4s);
+    check!(interval.humanize().units(2).to_string() == "1 day and 2 hours");
+    check!(interval.humanize().units(3).to_string() == "1 day, 2 hours, and 3 minutes");
+    check!(interval.humanize().units(9).style(Style::Short).to_string() == "1d 2h 3m 4s");
+    check!(time_interval!(1d 3m).humanize().units(2).to_string() == "1 day");
+    check!(time_interval!(1d 3m).humanize().units(3).to_string() == "1 day and 3 minutes");
+    check!(interval.humanize().largest(Unit::Hour).to_string() == "26 hours");
+    check!(
+      interval.humanize().largest(Unit::Minute).units(2).to_string()
+        == "1563 minutes and 4 seconds"
+    );
+    check!(time_interval!(30s).humanize().smallest(Unit::Minute).to_string() == "0 minutes");
+    check!(
+      interval.humanize().smallest(Unit::Hour).largest(Unit::Minute).to_string() == "26 hours"
+    );
+  }
+
+  #[test]
+  fn test_humanize_rounding() {
+    for (interval, rounding, expected) in [
+      (time_interval!(1h 29m), Rounding::Nearest, "1 hour"),
+      (time_interval!(1h 30m), Rounding::Nearest, "2 hours"),
+      (time_interval!(1h 1s), Rounding::Up, "2 hours"),
+      (time_interval!(1h), Rounding::Up, "1 hour"),
+      (time_interval!(59m 45s), Rounding::Nearest, "1 hour"),
+      (time_interval!(23h 59m), Rounding::Up, "1 day"),
+      (time_interval!(6d 23h), Rounding::Nearest, "1 week"),
+      (time_interval!(-1h 45m), Rounding::Nearest, "-2 hours"),
+    ] {
+      check!(interval.humanize().rounding(rounding).to_string() == expected, "{interval:?}");
+    }
+    let interval = time_interval!(1d 23h 59m);
+    check!(interval.humanize().units(2).rounding(Rounding::Nearest).to_string() == "2 days");
+  }
+
+  #[test]
+  fn test_humanize_since() {
+    let reference = datetime! { 2012-04-21 11:00:00 };
+    for (dt, long, short) in [
+      (datetime! { 2012-04-21 08:00:00 }, "3 hours ago", "3h ago"),
+      (datetime! { 2012-04-21 10:59:59.5 }, "now", "now"),
+      (datetime! { 2012-04-21 11:00:00 }, "now", "now"),
+      (datetime! { 2012-04-21 11:00:30 }, "in 30 seconds", "in 30s"),
+      (datetime! { 2012-04-23 12:00:00 }, "in 2 days", "in 2d"),
+      (datetime! { 2011-04-21 11:00:00 }, "1 year ago", "1y ago"),
+    ] {
+      check!(dt.humanize_since(&reference).to_string() == long);
+      check!(dt.humanize_since(&reference).style(Style::Short).to_string() == short);
+    }
+  }
+
+  #[test]
+  fn test_renderer() {
+    struct Shouty;
+
+    impl Renderer for Shouty {
+      fn render(
+        &self, f: &mut fmt::Formatter<'_>, parts: &[(Unit, u64)], tense: Tense, _: Style,
+      ) -> fmt::Result {
+        let (unit, count) = parts[0];
+        write!(f, "{count} {unit:?} {tense:?}")
+      }
+    }
+
+    let dt = datetime! { 2012-04-21 08:00:00 };
+    let humanized = dt.humanize_since(&datetime! { 2012-04-21 11:00:00 }).renderer(&Shouty);
+    check!(humanized.to_string() == "3 Hour Past");
+    check!(format!("{humanized:?}") == "Humanized(3 Hour Past)");
+  }
+}
@@ -3,14 +3,20 @@
 //! A [`TimeInterval`] represents elapsed time, or "duration", and is the struct provided for doing
 //! "timestamp math".
 
+use std::fmt;
 use std::ops::Add;
 use std::ops::AddAssign;
 use std::ops::Div;
 use std::ops::Mul;
 use std::ops::Sub;
 use std::ops::SubAssign;
+use std::str::FromStr;
 
 use crate::DateTime;
+use crate::ParseError;
+use crate::ParseErrorKind;
+use crate::ParseResult;
+use crate::parse::Input;
 
 #[cfg(feature = "macros")]
 #[doc(hidden)]
@@ -59,6 +65,11 @@ macro_rules! time_interval {
 /// assert_eq!(time_interval!(10.5s), TimeInterval::new(10, 500_000_000));
 /// ```
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(
+  feature = "diesel-pg",
+  derive(diesel::AsExpression, diesel::FromSqlRow),
+  diesel(sql_type = diesel::sql_types::Interval)
+)]
 pub struct TimeInterval {
   seconds: i64,
   nanos: u32,
@@ -216,6 +227,147 @@ impl Div for TimeInterval {
   }
 }
 
+impl fmt::Display for TimeInterval {
+  /// Write the interval in the language used by [`time_interval!`], such as `1d 2h 3m 4.5s`.
+  ///
+  /// A negative interval is written with a single leading `-`, which applies to every component,
+  /// and an empty interval is written as `0s`.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let nanos = self.as_nanoseconds();
+    if nanos < 0 {
+      f.write_str("-")?;
+    }
+    let nanos = nanos.unsigned_abs();
+    let (secs, frac) = ((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32);
+    let mut sep = "";
+    for (value, unit) in [(secs / 86_400, 'd'), (secs / 3_600 % 24, 'h'), (secs / 60 % 60, 'm')] {
+      if value > 0 {
+        write!(f, "{sep}{value}{unit}")?;
+        sep = " ";
+      }
+    }
+    if secs % 60 > 0 || frac > 0 || sep.is_empty() {
+      f.write_str(sep)?;
+      write_seconds(f, secs % 60, frac)?;
+      f.write_str("s")?;
+    }
+    Ok(())
+  }
+}
+
+/// Write seconds and a fraction of a second, with no trailing zeros in the fraction.
+pub(crate) fn write_seconds(w: &mut impl fmt::Write, secs: u64, mut frac: u32) -> fmt::Result {
+  write!(w, "{secs}")?;
+  if frac > 0 {
+    let mut width = 9;
+    while frac % 10 == 0 {
+      frac /= 10;
+      width -= 1;
+    }
+    write!(w, ".{frac:0width$}")?;
+  }
+  Ok(())
+}
+
+impl FromStr for TimeInterval {
+  type Err = ParseError;
+
+  /// Parse an interval in the language used by [`time_interval!`], such as `1h 30m` or
+  /// `-10.5s`.
+  ///
+  /// In addition to days (`d`), hours (`h`), minutes (`m`), and seconds (`s`), this accepts
+  /// milliseconds (`ms`), microseconds (`us` or `µs`), and nanoseconds (`ns`). Each unit may
+  /// appear once, from largest to smallest, and may have a fractional part; whitespace between
+  /// components is optional.
+  fn from_str(s: &str) -> ParseResult<Self> {
+    const UNITS: [(&str, i128); 8] = [
+      ("d", 86_400_000_000_000),
+      ("h", 3_600_000_000_000),
+      ("m", 60_000_000_000),
+      ("s", 1_000_000_000),
+      ("ms", 1_000_000),
+      ("us", 1_000),
+      ("µs", 1_000),
+      ("ns", 1),
+    ];
+    const MAX: i128 = i64::MAX as i128 * 1_000_000_000;
+
+    let mut input = Input::new(s);
+    let sign = match input.peek() {
+      Some('+' | '-') => input.sign()? as i128,
+      _ => 1,
+    };
+    let mut nanos: i128 = 0;
+    let mut next_unit = 0;
+    loop {
+      while input.eat(' ') {}
+      if input.peek().is_none() && next_unit > 0 {
+        break;
+      }
+      let start = input.pos();
+      let (mantissa, scale) = input.decimal(false)?;
+      let unit_start = input.pos();
+      while input.peek().is_some_and(|c| c.is_alphabetic()) {
+        input.bump();
+      }
+      let unit = input.since(unit_start);
+      let Some(ix) = UNITS.iter().position(|(u, _)| *u == unit) else {
+        return Err(input.err_at(unit_start, match unit.is_empty() && input.peek().is_none() {
+          true => ParseErrorKind::InputTooShort,
+          false => ParseErrorKind::Unexpected,
+        }));
+      };
+      if ix < next_unit {
+        return Err(input.err_at(unit_start, ParseErrorKind::Unexpected));
+      }
+      next_unit = if UNITS[ix].0 == "us" { ix + 2 } else { ix + 1 };
+      nanos = (mantissa.checked_mul(UNITS[ix].1))
+        .and_then(|n| nanos.checked_add(n / scale))
+        .filter(|n| *n <= MAX)
+        .ok_or_else(|| input.err_at(start, ParseErrorKind::InvalidTime))?;
+    }
+    Ok(Self::from_nanoseconds(sign * nanos))
+  }
+}
+
+impl Input<'_> {
+  /// Parse a non-negative decimal number, returning it as a mantissa and a power of ten to divide
+  /// it by.
+  ///
+  /// The decimal separator is `.` (or also `,`, if `comma` is set). Fractional digits beyond the
+  /// eighteenth are ignored.
+  pub(crate) fn decimal(&mut self, comma: bool) -> ParseResult<(i128, i128)> {
+    let start = self.pos();
+    let (mut mantissa, mut scale) = (0i128, 1i128);
+    let mut fraction = None;
+    while let Some(c) = self.peek() {
+      match (c, fraction) {
+        ('0'..='9', Some(18..)) => {},
+        ('0'..='9', _) => {
+          let digit = c as i128 - '0' as i128;
+          mantissa = (mantissa.checked_mul(10).and_then(|m| m.checked_add(digit)))
+            .ok_or_else(|| self.err_at(start, ParseErrorKind::InvalidTime))?;
+          if let Some(digits) = fraction.as_mut() {
+            *digits += 1;
+            scale *= 10;
+          }
+        },
+        ('.', None) if self.pos() > start => fraction = Some(0),
+        (',', None) if comma && self.pos() > start => fraction = Some(0),
+        _ => break,
+      }
+      self.bump();
+    }
+    if self.pos() == start || fraction == Some(0) {
+      return self.fail(match self.peek() {
+        Some(_) => ParseErrorKind::Unexpected,
+        None => ParseErrorKind::InputTooShort,
+      });
+    }
+    Ok((mantissa, scale))
+  }
+}
+
 #[cfg(feature = "syn")]
 mod syn {
   use datetime_rs_codegen::Delta;
@@ -387,4 +539,71 @@ mod tests {
     check!(dur.as_microseconds() == 5_000_000);
     check!(dur.as_nanoseconds() == 5_000_000_000);
   }
+
+  #[test]
+  fn test_display() {
+    for (interval, expected) in [
+      (time_interval!(0s), "0s"),
+      (time_interval!(1d 2h 3m 4.5s), "1d 2h 3m 4.5s"),
+      (time_interval!(1d 3m), "1d 3m"),
+      (time_interval!(90m), "1h 30m"),
+      (time_interval!(0.25s), "0.25s"),
+      (time_interval!(-1h 30m), "-1h 30m"),
+      (time_interval!(-1.25s), "-1.25s"),
+      (time_interval!(-0.5s), "-0.5s"),
+      (TimeInterval::new(0, 1), "0.000000001s"),
+      (TimeInterval::new(-1, 999_999_999), "-0.000000001s"),
+    ] {
+      check!(interval.to_string() == expected);
+    }
+  }
+
+  #[test]
+  fn test_from_str() -> ParseResult<()> {
+    for (s, expected) in [
+      ("0s", time_interval!(0s)),
+      ("1d 2h 3m 4.5s", time_interval!(1d 2h 3m 4.5s)),
+      ("1h30m", time_interval!(1h 30m)),
+      ("  90m ", time_interval!(90m)),
+      ("-1h 30m", time_interval!(-1h 30m)),
+      ("+20s", time_interval!(20s)),
+      ("-1.25s", time_interval!(-1.25s)),
+      ("1.5h", time_interval!(1h 30m)),
+      ("1s 500ms", time_interval!(1.5s)),
+      ("250ms", TimeInterval::from_milliseconds(250)),
+      ("1.5ms", TimeInterval::from_microseconds(1_500)),
+      ("10us 5ns", TimeInterval::from_nanoseconds(10_005)),
+      ("10µs", TimeInterval::from_microseconds(10)),
+      ("0.0000000001s", TimeInterval::new(0, 0)),
+    ] {
+      check!(s.parse::<TimeInterval>()? == expected, "{s}");
+    }
+    for interval in
+      [time_interval!(1d 2h 3m 4.5s), time_interval!(-0.5s), TimeInterval::new(-3, 1)]
+    {
+      check!(interval.to_string().parse::<TimeInterval>()? == interval);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_from_str_errors() {
+    for (s, index, kind) in [
+      ("", 0, ParseErrorKind::InputTooShort),
+      ("-", 1, ParseErrorKind::InputTooShort),
+      ("5", 1, ParseErrorKind::InputTooShort),
+      ("5x", 1, ParseErrorKind::Unexpected),
+      ("1h x", 3, ParseErrorKind::Unexpected),
+      ("1.s", 2, ParseErrorKind::Unexpected),
+      ("30s 1m", 5, ParseErrorKind::Unexpected),
+      ("1h 1h", 4, ParseErrorKind::Unexpected),
+      ("1us 1µs", 5, ParseErrorKind::Unexpected),
+      ("99999999999999999999d", 0, ParseErrorKind::InvalidTime),
+      ("1d 170141183460469231731687303715884105727ns", 3, ParseErrorKind::InvalidTime),
+    ] {
+      let err = s.parse::<TimeInterval>().unwrap_err();
+      check!(err.index == Some(index), "{s:?}");
+      check!(err.kind == kind, "{s:?}");
+    }
+  }
 }
@@ -0,0 +1,543 @@
+//! ISO 8601 timestamps, in their many forms, and durations.
+//!
+//! RFC 3339 is a profile of ISO 8601; timestamps in that profile are handled in `rfc3339.rs`. This
+//! module handles the rest of the standard: basic format, ordinal and week dates, reduced
+//! precision, decimal fractions of hours and minutes, and durations (`PT1H30M`).
+
+use std::fmt::Write;
+
+use date::Date;
+use date::interval::DateInterval;
+
+use crate::DateTime;
+use crate::FromDate;
+use crate::ParseErrorKind;
+use crate::ParseResult;
+use crate::Precision;
+use crate::format::Padding;
+use crate::interval;
+use crate::interval::TimeInterval;
+use crate::parse::Input;
+use crate::parse::days_in_month;
+use crate::rfc3339::Fraction;
+use crate::rfc3339::Layout;
+use crate::rfc3339::MAX_LEN;
+use crate::rfc3339::Offset;
+
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+
+impl DateTime {
+  /// Parse an ISO 8601 timestamp.
+  ///
+  /// In addition to the forms accepted by [`DateTime::parse_rfc3339`], this accepts:
+  ///
+  /// - Basic format, without separators (`20240704T153045Z`).
+  /// - Ordinal dates (`2024-186`) and week dates (`2024-W27-4`, or `2024-W27` for its Monday).
+  /// - Reduced precision times (`2024-07-04T15`, `2024-07-04T15:30`), or no time at all
+  ///   (`2024-07-04`), which is taken as midnight.
+  /// - A decimal fraction, with `.` or `,`, on the smallest time unit given (`2024-07-04T15.5` is
+  ///   15:30).
+  /// - `24:00` as the end of the day (and therefore midnight of the following day).
+  /// - UTC offsets of `Z`, `±HH`, and either `±HH:MM` (extended format) or `±HHMM` (basic format).
+  ///
+  /// Basic and extended format may not be mixed within a timestamp. As with RFC 3339, a `Z`
+  /// offset yields a date and time with no time zone.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::DateTime;
+  /// use datetime::datetime;
+  ///
+  /// let expected = datetime! { 2024-07-04 15:30:00 };
+  /// for s in ["20240704T1530Z", "2024-186T15:30", "2024-W27-4T15.5", "2024-07-04T15:30:00,0"] {
+  ///   assert_eq!(DateTime::parse_iso8601(s).unwrap(), expected);
+  /// }
+  /// ```
+  pub fn parse_iso8601(s: impl AsRef<str>) -> ParseResult<Self> {
+    let mut input = Input::new(s.as_ref());
+    let (mut date, extended) = input.iso_date()?;
+    let (mut nanos, mut offset) = (0, None);
+    if let Some('T' | 't') = input.peek() {
+      input.bump();
+      nanos = input.iso_time(extended)?;
+      offset = input.iso_offset(extended)?;
+    }
+    input.assert_consumed()?;
+
+    // Assemble the final value, rolling `24:00` over to the following day.
+    if nanos == NANOS_PER_DAY {
+      date += DateInterval::new(1);
+      nanos = 0;
+    }
+    let seconds = (nanos / 1_000_000_000) as u32;
+    let builder = date
+      .hms((seconds / 3_600) as u8, (seconds % 3_600 / 60) as u8, (seconds % 60) as u8)
+      .nanos((nanos % 1_000_000_000) as u32);
+    Ok(match offset {
+      Some(offset) => builder.utc_offset(offset).build(),
+      None => builder.build(),
+    })
+  }
+
+  /// Format this date and time as an ISO 8601 timestamp in extended format (the same as
+  /// [`DateTime::to_rfc3339`]), with exactly the given precision.
+  pub fn to_iso8601(&self, precision: Precision) -> String {
+    self.to_rfc3339(precision)
+  }
+
+  /// Format this date and time as an ISO 8601 timestamp in basic format (without separators), with
+  /// exactly the given precision.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::Precision;
+  /// use datetime::datetime;
+  ///
+  /// let dt = datetime! { 2024-07-04 15:30:45.5 };
+  /// assert_eq!(dt.to_iso8601_basic(Precision::Second), "20240704T153045Z");
+  /// assert_eq!(dt.to_iso8601_basic(Precision::Millisecond), "20240704T153045.500Z");
+  /// ```
+  pub fn to_iso8601_basic(&self, precision: Precision) -> String {
+    let layout = Layout {
+      sep: b'T',
+      extended: false,
+      fraction: Fraction::Exact(precision.digits()),
+      offset: Offset::Basic,
+    };
+    self.layout_str(layout, &mut [0; MAX_LEN]).to_owned()
+  }
+}
+
+impl TimeInterval {
+  /// Parse an ISO 8601 duration, such as `PT1H30M`, `-PT0.5S`, or `P2DT3H`.
+  ///
+  /// Days are interpreted as exactly 24 hours, and weeks as exactly 7 days. The smallest unit
+  /// given may have a decimal fraction, with `.` or `,`. Years and months cannot be converted to
+  /// an exact duration, so durations containing them are rejected with
+  /// [`ParseErrorKind::InexactDuration`].
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::interval::TimeInterval;
+  /// use datetime::time_interval;
+  ///
+  /// assert_eq!(TimeInterval::parse_iso8601("PT36H").unwrap(), time_interval!(36h));
+  /// assert_eq!(TimeInterval::parse_iso8601("P2DT3H").unwrap(), time_interval!(2d 3h));
+  /// assert_eq!(TimeInterval::parse_iso8601("-PT0.5S").unwrap(), time_interval!(-0.5s));
+  /// assert!(TimeInterval::parse_iso8601("P1M").is_err());
+  /// ```
+  pub fn parse_iso8601(s: impl AsRef<str>) -> ParseResult<Self> {
+    // Designators in the order they must appear, and their lengths in nanoseconds; years and
+    // months have no exact length.
+    const DESIGNATORS: [(char, bool, i128); 7] = [
+      ('Y', false, 0),
+      ('M', false, 0),
+      ('W', false, 7 * 86_400_000_000_000),
+      ('D', false, 86_400_000_000_000),
+      ('H', true, 3_600_000_000_000),
+      ('M', true, 60_000_000_000),
+      ('S', true, 1_000_000_000),
+    ];
+    const MAX: i128 = i64::MAX as i128 * 1_000_000_000;
+
+    let mut input = Input::new(s.as_ref());
+    let sign = match input.peek() {
+      Some('+' | '-') => input.sign()? as i128,
+      _ => 1,
+    };
+    input.expect('P')?;
+    let (mut nanos, mut next, mut time, mut fraction) = (0i128, 0, false, false);
+    loop {
+      if !time && input.eat('T') {
+        time = true;
+        next = 4;
+      }
+      let start = input.pos();
+      if input.peek().is_none() && next > 0 && !(time && next == 4) {
+        break;
+      }
+      if fraction {
+        return input.fail(ParseErrorKind::Unexpected);
+      }
+      let (mantissa, scale) = input.decimal(true)?;
+      fraction = scale > 1;
+      let designator_start = input.pos();
+      let designator = input.bump();
+      let Some(ix) = (next..DESIGNATORS.len())
+        .find(|&ix| Some(DESIGNATORS[ix].0) == designator && DESIGNATORS[ix].1 == time)
+      else {
+        return Err(input.err_at(designator_start, match designator {
+          Some(_) => ParseErrorKind::Unexpected,
+          None => ParseErrorKind::InputTooShort,
+        }));
+      };
+      if DESIGNATORS[ix].2 == 0 {
+        return Err(input.err_at(start, ParseErrorKind::InexactDuration));
+      }
+      next = ix + 1;
+      nanos = (mantissa.checked_mul(DESIGNATORS[ix].2))
+        .and_then(|n| nanos.checked_add(n / scale))
+        .filter(|n| *n <= MAX)
+        .ok_or_else(|| input.err_at(start, ParseErrorKind::InvalidTime))?;
+    }
+    Ok(Self::from_nanoseconds(sign * nanos))
+  }
+
+  /// Format this interval as an ISO 8601 duration, such as `PT1H30M` or `-PT0.5S`.
+  ///
+  /// The largest unit used is hours (`PT36H`, not `P1DT12H`), because days are not always 24
+  /// hours long when the duration is applied to a calendar. An empty interval is `PT0S`.
+  pub fn to_iso8601(&self) -> String {
+    let nanos = self.as_nanoseconds();
+    let mut s = String::from(if nanos < 0 { "-PT" } else { "PT" });
+    let nanos = nanos.unsigned_abs();
+    let (secs, frac) = ((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32);
+    for (value, unit) in [(secs / 3_600, 'H'), (secs / 60 % 60, 'M')] {
+      if value > 0 {
+        write!(s, "{value}{unit}").expect("writing to a String cannot fail");
+      }
+    }
+    if secs % 60 > 0 || frac > 0 || s.ends_with('T') {
+      interval::write_seconds(&mut s, secs % 60, frac).expect("writing to a String cannot fail");
+      s.push('S');
+    }
+    s
+  }
+}
+
+impl Input<'_> {
+  /// Parse a calendar, ordinal, or week date, in either basic or extended format.
+  ///
+  /// Returns the date, and whether it was in extended format.
+  fn iso_date(&mut self) -> ParseResult<(Date, bool)> {
+    let year = self.int(4, Padding::Zero)? as i16;
+    let extended = self.eat('-');
+
+    // Week dates.
+    if self.eat('W') {
+      let start = self.pos();
+      let week = self.int(2, Padding::Zero)? as i32;
+      let has_day = match extended {
+        true => self.eat('-'),
+        false => matches!(self.peek(), Some('0'..='9')),
+      };
+      let day_start = self.pos();
+      let day = if has_day { self.int(1, Padding::Zero)? as i32 } else { 1 };
+      if !(1..=7).contains(&day) {
+        return Err(self.err_at(day_start, ParseErrorKind::InvalidDate));
+      }
+      let date = iso_week_one(year) + DateInterval::new((week - 1) * 7 + day - 1);
+      if week == 0 || date >= iso_week_one(year + 1) {
+        return Err(self.err_at(start, ParseErrorKind::InvalidDate));
+      }
+      return Ok((date, extended));
+    }
+
+    // Calendar and ordinal dates are distinguished by the number of digits that follow.
+    let start = self.pos();
+    let date = match (extended, self.digits_ahead()) {
+      (_, 3) => {
+        let ordinal = self.int(3, Padding::Zero)? as i32;
+        let days_in_year = if days_in_month(year, 2) == 29 { 366 } else { 365 };
+        if ordinal == 0 || ordinal > days_in_year {
+          return Err(self.err_at(start, ParseErrorKind::InvalidDate));
+        }
+        Date::new(year, 1, 1) + DateInterval::new(ordinal - 1)
+      },
+      (true, 2) | (false, 4) => {
+        let month = self.int(2, Padding::Zero)? as u8;
+        if !(1..=12).contains(&month) {
+          return Err(self.err_at(start, ParseErrorKind::InvalidDate));
+        }
+        if extended {
+          self.expect('-')?;
+        }
+        let start = self.pos();
+        let day = self.int(2, Padding::Zero)? as u8;
+        if day == 0 || day > days_in_month(year, month) {
+          return Err(self.err_at(start, ParseErrorKind::InvalidDate));
+        }
+        Date::new(year, month, day)
+      },
+      (_, 0) if self.peek().is_none() => self.fail(ParseErrorKind::IncompleteDate)?,
+      _ => self.fail(ParseErrorKind::Unexpected)?,
+    };
+    Ok((date, extended))
+  }
+
+  /// Parse a time of day, with hours and optionally minutes and seconds, the last of which may
+  /// have a decimal fraction.
+  ///
+  /// Returns the number of nanoseconds since midnight (which is exactly one day for `24:00`).
+  fn iso_time(&mut self, extended: bool) -> ParseResult<u64> {
+    const UNITS: [u64; 3] = [3_600_000_000_000, 60_000_000_000, 1_000_000_000];
+    let start = self.pos();
+    let mut nanos = 0;
+    let mut units = 0;
+    while units < UNITS.len() {
+      if units > 0 {
+        match (extended, self.peek()) {
+          (true, Some(':')) => {
+            self.bump();
+          },
+          (false, Some('0'..='9')) => {},
+          _ => break,
+        }
+      }
+      let unit_start = self.pos();
+      let value = self.int(2, Padding::Zero)? as u64;
+      if units > 0 && value >= 60 {
+        return Err(self.err_at(unit_start, ParseErrorKind::InvalidTime));
+      }
+      nanos += value * UNITS[units];
+      units += 1;
+    }
+
+    // The smallest unit may have a decimal fraction.
+    if let Some('.' | ',') = self.peek() {
+      self.bump();
+      let mut numerator: u128 = 0;
+      let mut denominator: u128 = 1;
+      while let Some(c @ '0'..='9') = self.peek() {
+        if denominator < 1_000_000_000_000_000_000 {
+          numerator = numerator * 10 + (c as u8 - b'0') as u128;
+          denominator *= 10;
+        }
+        self.bump();
+      }
+      if denominator == 1 {
+        return self.fail(match self.peek() {
+          Some(_) => ParseErrorKind::Unexpected,
+          None => ParseErrorKind::InputTooShort,
+        });
+      }
+      nanos += (numerator * UNITS[units - 1] as u128 / denominator) as u64;
+    }
+
+    // Only `24:00` (with any smaller units zero) may be written with hour 24.
+    if nanos > NANOS_PER_DAY {
+      return Err(self.err_at(start, ParseErrorKind::InvalidTime));
+    }
+    Ok(nanos)
+  }
+
+  /// Parse an optional UTC offset, returning it in seconds.
+  ///
+  /// `Z` yields `None`, as does the absence of an offset.
+  fn iso_offset(&mut self, extended: bool) -> ParseResult<Option<i32>> {
+    match self.peek() {
+      Some('Z' | 'z') => {
+        self.bump();
+        Ok(None)
+      },
+      Some('+' | '-') => {
+        let start = self.pos();
+        let sign = self.sign()?;
+        let hours = self.int(2, Padding::Zero)? as i32;
+        let minutes = match (extended, self.peek()) {
+          (true, Some(':')) => {
+            self.bump();
+            self.int(2, Padding::Zero)? as i32
+          },
+          (false, Some('0'..='9')) => self.int(2, Padding::Zero)? as i32,
+          _ => 0,
+        };
+        if hours >= 24 || minutes >= 60 {
+          return Err(self.err_at(start, ParseErrorKind::InvalidTime));
+        }
+        Ok(Some(sign * (hours * 3_600 + minutes * 60)))
+      },
+      _ => Ok(None),
+    }
+  }
+}
+
+/// The Monday of the first ISO week of the given year (which is the week containing January 4).
+fn iso_week_one(year: i16) -> Date {
+  let jan4 = Date::new(year, 1, 4);
+  let days_since_monday = (jan4.weekday() as i32 + 6) % 7;
+  jan4 - DateInterval::new(days_since_monday)
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+  use crate::time_interval;
+
+  #[test]
+  fn test_parse_iso8601() -> ParseResult<()> {
+    let expected = datetime! { 2024-07-04 15:30:45 };
+    for s in [
+      // Calendar dates.
+      "2024-07-04T15:30:45",
+      "20240704T153045",
+      "2024-07-04T15:30:45Z",
+      "20240704T153045Z",
+      "2024-07-04T17:30:45+02:00",
+      "20240704T173045+0200",
+      "2024-07-04T17:30:45+02",
+      "20240704T173045+02",
+      // Ordinal dates.
+      "2024-186T15:30:45",
+      "2024186T153045",
+      // Week dates.
+      "2024-W27-4T15:30:45",
+      "2024W274T153045",
+      // Decimal fractions.
+      "2024-07-04T15:30:45.000",
+      "2024-07-04T15:30:45,000",
+      "2024-07-04T15:30.75",
+      "20240704T1530,75",
+    ] {
+      check!(DateTime::parse_iso8601(s)? == expected, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_iso8601_reduced() -> ParseResult<()> {
+    for (s, expected) in [
+      ("2024-07-04", datetime! { 2024-07-04 00:00:00 }),
+      ("2024-07-04T15", datetime! { 2024-07-04 15:00:00 }),
+      ("2024-07-04T15:30", datetime! { 2024-07-04 15:30:00 }),
+      ("20240704T1530", datetime! { 2024-07-04 15:30:00 }),
+      ("2024-07-04T15.25", datetime! { 2024-07-04 15:15:00 }),
+      ("2024-07-04T15:30:45.123456789", datetime! { 2024-07-04 15:30:45.123456789 }),
+      ("2024-07-04T24:00", datetime! { 2024-07-05 00:00:00 }),
+      ("2024-12-31T24:00:00", datetime! { 2025-01-01 00:00:00 }),
+      ("2024-W27", datetime! { 2024-07-01 00:00:00 }),
+    ] {
+      check!(DateTime::parse_iso8601(s)? == expected, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_week_dates() -> ParseResult<()> {
+    for (s, expected) in [
+      ("2009-W01-1", date::date! { 2008-12-29 }),
+      ("2009-W53-7", date::date! { 2010-01-03 }),
+      ("2010-W01-1", date::date! { 2010-01-04 }),
+      ("2020-W53-5", date::date! { 2021-01-01 }),
+      ("2021-W52-7", date::date! { 2022-01-02 }),
+    ] {
+      check!(DateTime::parse_iso8601(s)?.date() == expected, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_iso8601_errors() {
+    for (s, index, kind) in [
+      ("2024-07-04T153045", 13, ParseErrorKind::InputTooLong),
+      ("20240704T15:30:45", 11, ParseErrorKind::InputTooLong),
+      ("2024-07-04T15:30:45+0200", 22, ParseErrorKind::InputTooLong),
+      ("2024-13-04", 5, ParseErrorKind::InvalidDate),
+      ("2023-366", 5, ParseErrorKind::InvalidDate),
+      ("2021-W53-1", 6, ParseErrorKind::InvalidDate),
+      ("2021-W00-1", 6, ParseErrorKind::InvalidDate),
+      ("2021-W01-8", 9, ParseErrorKind::InvalidDate),
+      ("2024-07-04T25:00", 11, ParseErrorKind::InvalidTime),
+      ("2024-07-04T24:30", 11, ParseErrorKind::InvalidTime),
+      ("2024-07-04T15:60", 14, ParseErrorKind::InvalidTime),
+      ("2024-07-04T15:30.", 17, ParseErrorKind::InputTooShort),
+      ("2024-07", 7, ParseErrorKind::InputTooShort),
+      ("2024", 4, ParseErrorKind::IncompleteDate),
+      ("2024-0704", 5, ParseErrorKind::Unexpected),
+    ] {
+      let err = DateTime::parse_iso8601(s).unwrap_err();
+      check!(err.index == Some(index), "{s}");
+      check!(err.kind == kind, "{s}");
+    }
+  }
+
+  #[test]
+  fn test_to_iso8601() {
+    let dt = datetime! { 2024-07-04 15:30:45.123456789 };
+    check!(dt.to_iso8601(Precision::Second) == "2024-07-04T15:30:45Z");
+    check!(dt.to_iso8601(Precision::Microsecond) == "2024-07-04T15:30:45.123456Z");
+    check!(dt.to_iso8601_basic(Precision::Second) == "20240704T153045Z");
+    check!(dt.to_iso8601_basic(Precision::Nanosecond) == "20240704T153045.123456789Z");
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_to_iso8601_tz() -> ParseResult<()> {
+    let dt = datetime! { 2024-07-04 15:30:45 us::EASTERN };
+    check!(dt.to_iso8601(Precision::Second) == "2024-07-04T15:30:45-04:00");
+    check!(dt.to_iso8601_basic(Precision::Second) == "20240704T153045-0400");
+    let basic = dt.to_iso8601_basic(Precision::Millisecond);
+    check!(DateTime::parse_iso8601(&basic)? == dt);
+    check!(DateTime::parse_iso8601(&basic)?.hour() == 15);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_interval() -> ParseResult<()> {
+    for (s, expected) in [
+      ("PT0S", time_interval!(0s)),
+      ("PT36H", time_interval!(36h)),
+      ("PT1H30M", time_interval!(1h 30m)),
+      ("PT1.5H", time_interval!(1h 30m)),
+      ("PT0,25S", time_interval!(0.25s)),
+      ("-PT0.5S", time_interval!(-0.5s)),
+      ("+PT1M", time_interval!(1m)),
+      ("P2DT3H", time_interval!(2d 3h)),
+      ("P1W", time_interval!(7d)),
+      ("P1DT1H1M1.000000001S", time_interval!(1d 1h 1m 1.000000001s)),
+      ("P1D", time_interval!(1d)),
+    ] {
+      check!(TimeInterval::parse_iso8601(s)? == expected, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_interval_errors() {
+    for (s, index, kind) in [
+      ("", 0, ParseErrorKind::InputTooShort),
+      ("1H", 0, ParseErrorKind::Unexpected),
+      ("P", 1, ParseErrorKind::InputTooShort),
+      ("PT", 2, ParseErrorKind::InputTooShort),
+      ("P1DT", 4, ParseErrorKind::InputTooShort),
+      ("P1Y", 1, ParseErrorKind::InexactDuration),
+      ("P1M", 1, ParseErrorKind::InexactDuration),
+      ("P1Y2DT3H", 1, ParseErrorKind::InexactDuration),
+      ("PT1H1H", 5, ParseErrorKind::Unexpected),
+      ("PT1M1H", 5, ParseErrorKind::Unexpected),
+      ("P1H", 2, ParseErrorKind::Unexpected),
+      ("PT1D", 3, ParseErrorKind::Unexpected),
+      ("PT1", 3, ParseErrorKind::InputTooShort),
+      ("PT0.5H30M", 6, ParseErrorKind::Unexpected),
+      ("PT1.S", 4, ParseErrorKind::Unexpected),
+      ("P99999999999999999W", 1, ParseErrorKind::InvalidTime),
+      ("PT1H170141183460469231731687303715S", 4, ParseErrorKind::InvalidTime),
+    ] {
+      let err = TimeInterval::parse_iso8601(s).unwrap_err();
+      check!(err.index == Some(index), "{s:?}");
+      check!(err.kind == kind, "{s:?}");
+    }
+  }
+
+  #[test]
+  fn test_interval_to_iso8601() -> ParseResult<()> {
+    for (interval, expected) in [
+      (time_interval!(0s), "PT0S"),
+      (time_interval!(36h), "PT36H"),
+      (time_interval!(1d 2h 3m 4.5s), "PT26H3M4.5S"),
+      (time_interval!(90s), "PT1M30S"),
+      (time_interval!(-0.5s), "-PT0.5S"),
+      (time_interval!(-1h 30m), "-PT1H30M"),
+      (TimeInterval::new(0, 1), "PT0.000000001S"),
+    ] {
+      check!(interval.to_iso8601() == expected);
+      check!(TimeInterval::parse_iso8601(interval.to_iso8601())? == interval);
+    }
+    Ok(())
+  }
+}
@@ -6,15 +6,12 @@
 #![doc(html_root_url = "https://docs.rs/datetime-rs/latest")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::fmt;
 use std::str::FromStr;
 use std::time::SystemTime;
 
-use format::FormattedDateTime;
-use strptime::ParseError;
-use strptime::ParseResult;
-use strptime::Parser;
 use strptime::RawDateTime;
 
 /// Construct a date and time from a `YYYY-MM-DD HH:MM:SS[.fraction]` literal.
@@ -35,12 +32,14 @@ macro_rules! datetime {
     #[allow(clippy::zero_prefixed_literal)]
     {
       const __SN: (u8, u32) = $crate::__private::parse_second(::core::stringify!($s));
+      const __TZ: ::core::option::Option<&str> =
+        $crate::__private::tz_name(::core::stringify!($($tz)::+));
       match $crate::DateTime::ymd($y, $m, $d)
         .hms($h, $mi, __SN.0)
         .nanos(__SN.1)
         .tz($crate::tz::$($tz)::+)
       {
-        Ok(dt) => dt.build(),
+        Ok(dt) => $crate::__private::named(dt, __TZ).build(),
         Err(_) => panic!("invalid date/time and time zone combination"),
       }
     }
@@ -101,20 +100,115 @@ pub mod __private {
     }
     (sec, nanos)
   }
+
+  /// Find the IANA name of the time zone at the given path under `tz`, such as `europe :: ZURICH`
+  /// for `Europe/Zurich`, at compile time.
+  ///
+  /// Path segments correspond to the parts of the name, and underscores to underscores or hyphens;
+  /// case is ignored.
+  #[cfg(feature = "tz")]
+  pub const fn tz_name(path: &str) -> Option<&'static str> {
+    let path = path.as_bytes();
+    let mut i = 0;
+    'names: while i < tzdb::TZ_NAMES.len() {
+      let name = tzdb::TZ_NAMES[i].as_bytes();
+      i += 1;
+      let (mut p, mut n) = (0, 0);
+      while p < path.len() {
+        let matched = match (path[p], if n < name.len() { name[n] } else { 0 }) {
+          (b' ', _) => {
+            p += 1;
+            continue;
+          },
+          (b':', b'/') if p + 1 < path.len() && path[p + 1] == b':' => {
+            p += 1;
+            true
+          },
+          (b'_', b'-') => true,
+          (a, b) => a.eq_ignore_ascii_case(&b),
+        };
+        if !matched {
+          continue 'names;
+        }
+        (p, n) = (p + 1, n + 1);
+      }
+      if n == name.len() {
+        return Some(tzdb::TZ_NAMES[i - 1]);
+      }
+    }
+    None
+  }
+
+  /// Attach the IANA name found by [`tz_name`] to a builder's time zone.
+  #[cfg(feature = "tz")]
+  pub const fn named(
+    mut builder: crate::DateTimeBuilder, name: Option<&'static str>,
+  ) -> crate::DateTimeBuilder {
+    if let (Some(name), crate::tz::TimeZone::Tz(tz)) = (name, builder.tz) {
+      builder.tz = crate::tz::TimeZone::Named(name, tz);
+    }
+    builder
+  }
 }
 
+#[cfg(feature = "diesel-mysql")]
+mod diesel_mysql;
 #[cfg(feature = "diesel-pg")]
-mod diesel_pg;
+#[cfg_attr(docsrs, doc(cfg(feature = "diesel-pg")))]
+pub mod diesel_pg;
+#[cfg(feature = "diesel-sqlite")]
+mod diesel_sqlite;
 #[cfg(feature = "duckdb")]
 mod duckdb;
 mod format;
+mod http_date;
+pub mod humanize;
 pub mod interval;
+mod iso8601;
+mod parse;
+#[cfg(any(feature = "diesel-pg", feature = "postgres", feature = "sqlx-postgres"))]
+mod pg;
+#[cfg(feature = "postgres")]
+mod postgres;
+pub mod range;
+#[cfg(feature = "tz")]
+mod relative;
+mod rfc2822;
+mod rfc3339;
+#[cfg(feature = "rusqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rusqlite")))]
+pub mod rusqlite;
 #[cfg(feature = "serde")]
-mod serde;
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde;
+#[cfg(any(feature = "diesel-sqlite", feature = "rusqlite", feature = "sqlx-sqlite"))]
+mod sqlite;
+#[cfg(feature = "sqlx-postgres")]
+mod sqlx_postgres;
+#[cfg(feature = "sqlx-sqlite")]
+mod sqlx_sqlite;
+#[cfg(any(
+  feature = "diesel-mysql",
+  feature = "diesel-pg",
+  feature = "duckdb",
+  feature = "postgres",
+  feature = "sqlx-postgres"
+))]
+mod storage;
+#[cfg(feature = "tz")]
+mod zone1970;
 
 pub use date::Date;
 pub use date::Weekday;
 pub use date::date;
+pub use format::FormatError;
+pub use format::FormatErrorKind;
+pub use format::FormattedDateTime;
+pub use parse::ParseError;
+pub use parse::ParseErrorKind;
+pub use parse::ParseResult;
+#[cfg(feature = "tz")]
+pub use relative::RelativeDateTime;
 
 /// Time zone compnents.
 ///
@@ -122,35 +216,107 @@ pub use date::date;
 #[cfg(feature = "tz")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tz")))]
 pub mod tz {
+  use std::collections::HashMap;
+  use std::hash::Hash;
+  use std::hash::Hasher;
+  use std::sync::OnceLock;
+
   pub use date::tz::*;
 
   #[derive(Clone, Copy, Debug, Eq, PartialEq)]
   pub(crate) enum TimeZone {
     Unspecified,
     Tz(crate::tz::TimeZoneRef<'static>),
+    /// A time zone whose IANA name is known, such as one parsed from an RFC 9557 annotation.
+    Named(&'static str, crate::tz::TimeZoneRef<'static>),
     FixedOffset(i32),
   }
 
+  /// Coordinated Universal Time.
+  #[cfg(any(feature = "diesel-pg", feature = "postgres", feature = "sqlx-postgres"))]
+  pub(crate) const UTC: TimeZone = TimeZone::Named("UTC", tzdb::time_zone::UTC);
+
   impl TimeZone {
     pub(crate) const fn ut_offset(&self, timestamp: i64) -> TzResult<i32> {
       match self {
         Self::Unspecified => Ok(0),
         Self::FixedOffset(offset) => Ok(*offset),
-        Self::Tz(tz) => match tz.find_local_time_type(timestamp) {
+        Self::Tz(tz) | Self::Named(_, tz) => match tz.find_local_time_type(timestamp) {
           Ok(t) => Ok(t.ut_offset()),
           Err(e) => Err(e),
         },
       }
     }
+
+    /// The IANA name of this time zone, if it has one.
+    pub(crate) fn name(&self) -> Option<&'static str> {
+      match self {
+        Self::Named(name, _) => Some(name),
+        Self::Tz(tz) => name_of(*tz),
+        Self::Unspecified | Self::FixedOffset(_) => None,
+      }
+    }
+  }
+
+  /// Look up a time zone by its IANA name, ignoring case.
+  ///
+  /// Returns the name as spelled in the time zone database, along with the time zone.
+  pub(crate) fn by_name(name: &str) -> Option<(&'static str, TimeZoneRef<'static>)> {
+    let name = tzdb::TZ_NAMES.iter().find(|n| n.eq_ignore_ascii_case(name))?;
+    Some((name, tz_by_name(name)?))
+  }
+
+  /// Find the IANA name of a time zone.
+  ///
+  /// Zones with identical rules share the same data, so they cannot be told apart. Among them,
+  /// this prefers `UTC`, then the canonical name (so `Europe/Zurich` rather than its link
+  /// `Europe/Busingen`), then the alphabetically first name. The names are indexed on first use.
+  fn name_of(tz: TimeZoneRef<'static>) -> Option<&'static str> {
+    static NAMES: OnceLock<HashMap<ZoneKey, &'static str>> = OnceLock::new();
+    let rank = |name: &str| match name {
+      "UTC" => 0,
+      _ if crate::zone1970::CANONICAL.contains(&name) => 1,
+      _ => 2,
+    };
+    let names = NAMES.get_or_init(|| {
+      let mut names = HashMap::<ZoneKey, &'static str>::new();
+      for &name in tzdb::TZ_NAMES {
+        let Some(tz) = tz_by_name(name) else { continue };
+        let best = names.entry(ZoneKey(tz)).or_insert(name);
+        if rank(name) < rank(best) {
+          *best = name;
+        }
+      }
+      names
+    });
+    names.get(&ZoneKey(tz)).copied()
+  }
+
+  /// A time zone, hashed by a summary of its rules and compared by all of them.
+  #[derive(Eq, PartialEq)]
+  struct ZoneKey(TimeZoneRef<'static>);
+
+  impl Hash for ZoneKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+      self.0.transitions().len().hash(state);
+      self.0.transitions().last().map(|t| t.unix_leap_time()).hash(state);
+      for local_time_type in self.0.local_time_types() {
+        local_time_type.ut_offset().hash(state);
+      }
+    }
   }
 }
 
 /// A representation of a date and time.
 #[derive(Clone, Copy, Eq)]
-#[cfg_attr(feature = "diesel-pg", derive(diesel::AsExpression, diesel::FromSqlRow))]
-#[cfg_attr(feature = "diesel-pg", diesel(
-    sql_type = diesel::sql_types::Timestamp,
-    sql_type = diesel::sql_types::Timestamptz))]
+#[cfg_attr(
+  any(feature = "diesel-mysql", feature = "diesel-pg", feature = "diesel-sqlite"),
+  derive(diesel::AsExpression, diesel::FromSqlRow),
+  diesel(sql_type = diesel::sql_types::Timestamp)
+)]
+#[cfg_attr(feature = "diesel-mysql", diesel(sql_type = diesel::sql_types::Datetime))]
+#[cfg_attr(feature = "diesel-pg", diesel(sql_type = diesel::sql_types::Timestamptz))]
+#[cfg_attr(feature = "diesel-sqlite", diesel(sql_type = diesel::sql_types::TimestamptzSqlite))]
 pub struct DateTime {
   seconds: i64,
   nanos: u32,
@@ -159,6 +325,18 @@ pub struct DateTime {
 }
 
 impl DateTime {
+  /// The latest supported date and time: the last nanosecond UTC of December 31, 32766.
+  ///
+  /// This leaves a year of headroom after the latest representable year, so that the wall clock
+  /// time is valid in any time zone.
+  pub const MAX: Self =
+    DateTime::ymd(i16::MAX - 1, 12, 31).hms(23, 59, 59).nanos(999_999_999).build();
+  /// The earliest supported date and time: midnight UTC on January 1, -32767.
+  ///
+  /// This leaves a year of headroom before the earliest representable year, so that the wall
+  /// clock time is valid in any time zone.
+  pub const MIN: Self = DateTime::ymd(i16::MIN + 1, 1, 1).build();
+
   /// Create a new date and time object.
   pub const fn ymd(year: i16, month: u8, day: u8) -> DateTimeBuilder {
     DateTimeBuilder {
@@ -189,12 +367,12 @@ impl DateTime {
 
   /// Create a new date and time object from the given Unix timestamp in milliseconds.
   pub const fn from_timestamp_millis(millis: i64) -> Self {
-    Self::from_timestamp(millis.div_euclid(1_000), millis.rem_euclid(1_000) as u32)
+    Self::from_timestamp(millis.div_euclid(1_000), millis.rem_euclid(1_000) as u32 * 1_000_000)
   }
 
   /// Create a new date and time object from the given Unix timestamp in microseconds.
   pub const fn from_timestamp_micros(micros: i64) -> Self {
-    Self::from_timestamp(micros.div_euclid(1_000_000), micros.rem_euclid(1_000_000) as u32)
+    Self::from_timestamp(micros.div_euclid(1_000_000), micros.rem_euclid(1_000_000) as u32 * 1_000)
   }
 
   /// Create a new date and time object from the given Unix timestamp in nanoseconds.
@@ -253,6 +431,17 @@ impl DateTime {
 
 /// Accessors
 impl DateTime {
+  /// The IANA name of this date and time's time zone, such as `America/New_York`.
+  ///
+  /// Returns `None` if there is no time zone, or only a fixed UTC offset. The name is preserved
+  /// from the [`datetime!`] macro or a parsed string. Otherwise it is looked up, and a time zone
+  /// that shares its rules with others reports its canonical name.
+  #[cfg(feature = "tz")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "tz")))]
+  pub fn tz_name(&self) -> Option<&'static str> {
+    self.tz.name()
+  }
+
   /// The year for this date.
   #[inline]
   pub const fn year(&self) -> i16 {
@@ -351,6 +540,57 @@ impl DateTime {
     }
   }
 
+  /// Whether this timestamp can be represented at the given precision with no fidelity loss.
+  #[inline]
+  pub const fn fits_precision(&self, precision: Precision) -> bool {
+    self.nanos % precision.unit() == 0
+  }
+
+  /// Reduce this date and time to the given precision, following `policy` for any fidelity that
+  /// would be lost.
+  ///
+  /// Returns `None` if the policy is [`PrecisionLoss::Error`] and this date and time does not fit
+  /// the precision. The time zone is preserved.
+  pub const fn to_precision(
+    mut self, precision: Precision, policy: PrecisionLoss,
+  ) -> Option<Self> {
+    if self.fits_precision(precision) {
+      return Some(self);
+    }
+    let unit = precision.unit() as i128;
+    let nanos = self.as_nanoseconds();
+    let (mut units, rem) = (nanos.div_euclid(unit), nanos.rem_euclid(unit));
+    match policy {
+      PrecisionLoss::Truncate => {},
+      PrecisionLoss::RoundHalfEven =>
+        if rem * 2 > unit || (rem * 2 == unit && units % 2 != 0) {
+          units += 1;
+        },
+      PrecisionLoss::Error => return None,
+    }
+    let nanos = units * unit;
+    self.seconds = nanos.div_euclid(1_000_000_000) as i64;
+    self.nanos = nanos.rem_euclid(1_000_000_000) as u32;
+    Some(self)
+  }
+
+  /// Reduce this date and time to the precision a database stores, following `policy`.
+  #[cfg(any(
+    feature = "diesel-mysql",
+    feature = "diesel-pg",
+    feature = "duckdb",
+    feature = "postgres",
+    feature = "rusqlite",
+    feature = "sqlx-postgres"
+  ))]
+  pub(crate) fn to_storage_precision(
+    self, precision: Precision, policy: PrecisionLoss,
+  ) -> Result<Self, String> {
+    self.to_precision(precision, policy).ok_or_else(|| {
+      format!("{self:?} cannot be stored with {precision:?} precision without losing fidelity")
+    })
+  }
+
   /// Provide the number of seconds since the epoch in the time zone with the same offset as this
   /// datetime's time zone.
   #[inline(always)]
@@ -374,16 +614,26 @@ impl DateTime {
 
 impl DateTime {
   /// Format the given date and time according to the provided `strftime`-like string.
-  pub fn format(&self, format: &'static str) -> FormattedDateTime<'_> {
-    FormattedDateTime { dt: self, format }
+  ///
+  /// The format string may be borrowed or owned. Unrecognized or malformed specifiers are written
+  /// out verbatim; use [`DateTime::try_format`] to reject them instead.
+  pub fn format<'a>(&'a self, format: impl Into<Cow<'a, str>>) -> FormattedDateTime<'a> {
+    FormattedDateTime { dt: self, format: format.into() }
+  }
+
+  /// Format the given date and time according to the provided `strftime`-like string, validating
+  /// the format string first.
+  pub fn try_format<'a>(
+    &'a self, format: impl Into<Cow<'a, str>>,
+  ) -> Result<FormattedDateTime<'a>, FormatError> {
+    FormattedDateTime::try_new(self, format.into())
   }
 }
 
 impl DateTime {
   /// Parse a date from a string, according to the provided format string.
-  pub fn parse(datetime_str: impl AsRef<str>, fmt: &'static str) -> ParseResult<Self> {
-    let parser = Parser::new(fmt);
-    parser.parse(datetime_str)?.try_into()
+  pub fn parse(datetime_str: impl AsRef<str>, fmt: impl AsRef<str>) -> ParseResult<Self> {
+    parse::parse(datetime_str.as_ref(), fmt.as_ref())
   }
 }
 
@@ -412,30 +662,20 @@ impl Ord for DateTime {
 impl FromStr for DateTime {
   type Err = ParseError;
 
-  #[rustfmt::skip]
+  /// Parse an ISO 8601 / RFC 3339 timestamp, such as `2012-04-21T11:00:00.5-04:00`.
+  ///
+  /// The date and time may be separated by `T`, `t`, or a space; fractional seconds of one to nine
+  /// digits are accepted; and the timestamp may end with `Z`, or a UTC offset of the form
+  /// `±HH:MM`, `±HHMM`, or `±HH`.
   fn from_str(s: &str) -> ParseResult<Self> {
-    // Attempt several common formats.
-    if let Ok(dt) = Parser::new("%Y-%m-%dT%H:%M:%S").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%dT%H:%M:%S%z").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%d %H:%M:%S").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%d %H:%M:%S%z").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%dT%H:%M:%S%.6f").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%dT%H:%M:%S%.6f%z").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%d %H:%M:%S%.6f").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%d %H:%M:%S%.6f%z").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%dT%H:%M:%S%.9f").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%dT%H:%M:%S%.9f%z").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%d %H:%M:%S%.9f").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%d %H:%M:%S%.9f%z").parse(s) { return dt.try_into(); }
-    if let Ok(dt) = Parser::new("%Y-%m-%d %H:%M:%SZ").parse(s) { return dt.try_into(); }
-    Parser::new("%Y-%m-%dT%H:%M:%SZ").parse(s)?.try_into()
+    rfc3339::parse_lenient(s)
   }
 }
 
 impl TryFrom<RawDateTime> for DateTime {
-  type Error = ParseError;
+  type Error = strptime::ParseError;
 
-  fn try_from(value: RawDateTime) -> ParseResult<Self> {
+  fn try_from(value: RawDateTime) -> strptime::ParseResult<Self> {
     let date = value.date()?;
     let time = value.time().unwrap_or_default();
     Ok(match time.utc_offset() {
@@ -457,15 +697,8 @@ impl TryFrom<RawDateTime> for DateTime {
 
 impl fmt::Debug for DateTime {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    if self.nanos == 0 {
-      write!(f, "{}", self.format("%Y-%m-%d %H:%M:%S"))
-    } else if self.nanos % 1_000_000 == 0 {
-      write!(f, "{}", self.format("%Y-%m-%d %H:%M:%S%.3f"))
-    } else if self.nanos % 1_000 == 0 {
-      write!(f, "{}", self.format("%Y-%m-%d %H:%M:%S%.6f"))
-    } else {
-      write!(f, "{}", self.format("%Y-%m-%d %H:%M:%S%.9f"))
-    }
+    let mut buf = [0; rfc3339::MAX_LEN];
+    f.write_str(self.layout_str(rfc3339::DEBUG, &mut buf))
   }
 }
 
@@ -524,10 +757,15 @@ impl DateTimeBuilder {
   /// This method assumes that the offset _modifies_ the underlying timestamp; in other words, the
   /// YMD/HMS specified to the date and time builder should be preserved, and the offset applied to
   /// the underlying timestamp to preserve the date and time on the wall clock.
-  #[cfg(feature = "tz")]
+  ///
+  /// Without the `tz` feature, the offset is still applied to the underlying timestamp, but is not
+  /// retained afterward.
   pub(crate) const fn utc_offset(mut self, offset: i32) -> Self {
     self.offset = offset as i64;
-    self.tz = tz::TimeZone::FixedOffset(offset);
+    #[cfg(feature = "tz")]
+    {
+      self.tz = tz::TimeZone::FixedOffset(offset);
+    }
     self
   }
 
@@ -575,14 +813,92 @@ pub enum Precision {
   Nanosecond,
 }
 
+impl Precision {
+  /// The number of nanoseconds in one unit of this precision.
+  const fn unit(self) -> u32 {
+    match self {
+      Self::Second => 1_000_000_000,
+      Self::Millisecond => 1_000_000,
+      Self::Microsecond => 1_000,
+      Self::Nanosecond => 1,
+    }
+  }
+}
+
+/// What to do when storing a date and time in a database with less precision than it has.
+///
+/// Database integrations (such as PostgreSQL, which stores microseconds) truncate a plain
+/// [`DateTime`], or leave the database to; wrap it in [`Rounded`] or [`Strict`] to store it
+/// under another policy.
+///
+/// ## Example
+///
+/// ```
+/// use datetime::DateTime;
+/// use datetime::Precision;
+/// use datetime::PrecisionLoss;
+///
+/// let dt = DateTime::from_timestamp(0, 1_500);
+/// let rounded = dt.to_precision(Precision::Microsecond, PrecisionLoss::RoundHalfEven);
+/// assert_eq!(rounded, Some(DateTime::from_timestamp(0, 2_000)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PrecisionLoss {
+  /// Discard the excess precision, rounding toward the past.
+  #[default]
+  Truncate,
+  /// Round to the nearest representable value, with ties going to the even one.
+  RoundHalfEven,
+  /// Refuse to store the value.
+  Error,
+}
+
+/// A date and time that database integrations store following [`PrecisionLoss::RoundHalfEven`],
+/// rather than truncating it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+  any(feature = "diesel-mysql", feature = "diesel-pg"),
+  derive(diesel::AsExpression),
+  diesel(sql_type = diesel::sql_types::Timestamp)
+)]
+#[cfg_attr(feature = "diesel-mysql", diesel(sql_type = diesel::sql_types::Datetime))]
+#[cfg_attr(feature = "diesel-pg", diesel(sql_type = diesel::sql_types::Timestamptz))]
+pub struct Rounded(pub DateTime);
+
+impl From<DateTime> for Rounded {
+  fn from(dt: DateTime) -> Self {
+    Self(dt)
+  }
+}
+
+/// A date and time that database integrations refuse to store if it has more precision than the
+/// database, following [`PrecisionLoss::Error`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+  any(feature = "diesel-mysql", feature = "diesel-pg"),
+  derive(diesel::AsExpression),
+  diesel(sql_type = diesel::sql_types::Timestamp)
+)]
+#[cfg_attr(feature = "diesel-mysql", diesel(sql_type = diesel::sql_types::Datetime))]
+#[cfg_attr(feature = "diesel-pg", diesel(sql_type = diesel::sql_types::Timestamptz))]
+pub struct Strict(pub DateTime);
+
+impl From<DateTime> for Strict {
+  fn from(dt: DateTime) -> Self {
+    Self(dt)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use assert2::check;
-  use strptime::ParseResult;
 
   use crate::DateTime;
+  use crate::FormatErrorKind;
   use crate::FromDate;
+  use crate::ParseResult;
   use crate::Precision;
+  use crate::PrecisionLoss;
   use crate::interval::TimeInterval;
   #[cfg(feature = "tz")]
   use crate::tz;
@@ -710,6 +1026,96 @@ mod tests {
     Ok(())
   }
 
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_tz_name() {
+    // The `datetime!` macro keeps the name of the constant it was given.
+    check!(datetime! { 2012-04-21 11:00:00 europe::ZURICH }.tz_name() == Some("Europe/Zurich"));
+    check!(datetime! { 2012-04-21 11:00:00 us::EASTERN }.tz_name() == Some("US/Eastern"));
+    let dt = datetime! { 2012-04-21 11:00:00 america::PORT_AU_PRINCE };
+    check!(dt.tz_name() == Some("America/Port-au-Prince"));
+    check!(crate::__private::tz_name("europe :: ATLANTIS").is_none());
+    check!(crate::__private::tz_name("europe :: ZURICH_X").is_none());
+
+    // Otherwise, the name is looked up from the rules.
+    let dt = DateTime::from_timestamp(1_335_020_400, 0);
+    check!(dt.with_tz(tz::us::EASTERN).tz_name() == Some("America/New_York"));
+    check!(dt.with_tz(tz::europe::BERLIN).tz_name() == Some("Europe/Berlin"));
+    check!(dt.with_tz(tz::europe::ZURICH).tz_name() == Some("Europe/Zurich"));
+    check!(dt.with_tz(tz::europe::BUSINGEN).tz_name() == Some("Europe/Zurich"));
+    check!(dt.with_tz(tz::asia::CALCUTTA).tz_name() == Some("Asia/Kolkata"));
+    for name in crate::zone1970::CANONICAL {
+      check!(tz::by_name(name).is_some(), "{name}");
+    }
+    check!(dt.tz_name() == None);
+  }
+
+  #[test]
+  fn test_unix_fraction() {
+    let expected = datetime! { 1969-12-31 23:59:59.5 };
+    check!(DateTime::from_timestamp_millis(-500) == expected);
+    check!(DateTime::from_timestamp_micros(-500_000) == expected);
+    check!(DateTime::from_timestamp_nanos(-500_000_000) == expected);
+  }
+
+  #[test]
+  fn test_fits_precision() {
+    let dt = datetime! { 2012-04-21 15:00:00.123456 };
+    check!(!dt.fits_precision(Precision::Second));
+    check!(!dt.fits_precision(Precision::Millisecond));
+    check!(dt.fits_precision(Precision::Microsecond));
+    check!(dt.fits_precision(Precision::Nanosecond));
+    check!(datetime! { 2012-04-21 15:00:00 }.fits_precision(Precision::Second));
+  }
+
+  #[test]
+  fn test_to_precision() {
+    use PrecisionLoss::*;
+    let dt = |s| DateTime::from_timestamp_nanos(s);
+    for (nanos, precision, truncated, rounded) in [
+      (1_500, Precision::Microsecond, 1_000, 2_000),
+      (2_500, Precision::Microsecond, 2_000, 2_000),
+      (2_501, Precision::Microsecond, 2_000, 3_000),
+      (2_499, Precision::Microsecond, 2_000, 2_000),
+      (-1_500, Precision::Microsecond, -2_000, -2_000),
+      (-2_500, Precision::Microsecond, -3_000, -2_000),
+      (1_500_000_000, Precision::Second, 1_000_000_000, 2_000_000_000),
+      (-500_000_000, Precision::Second, -1_000_000_000, 0),
+      (999_999_999, Precision::Millisecond, 999_000_000, 1_000_000_000),
+    ] {
+      check!(dt(nanos).to_precision(precision, Truncate) == Some(dt(truncated)), "{nanos}");
+      check!(dt(nanos).to_precision(precision, RoundHalfEven) == Some(dt(rounded)), "{nanos}");
+      check!(dt(nanos).to_precision(precision, Error) == None, "{nanos}");
+    }
+    for policy in [Truncate, RoundHalfEven, Error] {
+      check!(dt(1_000).to_precision(Precision::Microsecond, policy) == Some(dt(1_000)));
+    }
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_to_precision_tz() {
+    let dt = datetime! { 2012-04-21 11:00:00.9999999 america::NEW_YORK };
+    let rounded = dt.to_precision(Precision::Microsecond, PrecisionLoss::RoundHalfEven);
+    check!(rounded == Some(datetime! { 2012-04-21 11:00:01 america::NEW_YORK }));
+    check!(rounded.and_then(|dt| dt.tz_name()) == Some("America/New_York"));
+  }
+
+  #[test]
+  fn test_min_max() {
+    check!(format!("{:?}", DateTime::MIN) == "-32767-01-01 00:00:00");
+    check!(format!("{:?}", DateTime::MAX) == "32766-12-31 23:59:59.999999999");
+    check!(DateTime::MIN < datetime! { 0000-01-01 00:00:00 });
+    check!(DateTime::MAX > datetime! { 9999-12-31 23:59:59 });
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_min_max_tz() {
+    check!(DateTime::MIN.with_tz(tz::us::PACIFIC).year() == -32768);
+    check!(DateTime::MAX.with_tz(tz::asia::TOKYO).year() == 32767);
+  }
+
   #[cfg(feature = "tz")]
   #[test]
   fn test_unix_tz() {
@@ -749,6 +1155,36 @@ mod tests {
     check!(dt.hour() == 11);
   }
 
+  #[test]
+  fn test_runtime_format() {
+    let dt = datetime! { 2012-04-21 11:00:00 };
+    let fmt = String::from("%Y-%m-%d");
+    check!(dt.format(fmt.as_str()).to_string() == "2012-04-21");
+    check!(dt.format(fmt.clone()).to_string() == "2012-04-21");
+    check!(dt.try_format(fmt).unwrap().to_string() == "2012-04-21");
+  }
+
+  #[test]
+  fn test_try_format_error() {
+    let dt = datetime! { 2012-04-21 11:00:00 };
+    for (fmt, index, kind) in [
+      ("%Y-%m-%K", 7, FormatErrorKind::UnknownSpecifier('K')),
+      ("%Y-%m-%.3d", 9, FormatErrorKind::MisplacedModifier('d')),
+      ("%Y-%m-%", 6, FormatErrorKind::Incomplete),
+    ] {
+      let err = dt.try_format(fmt).map(|f| f.to_string()).unwrap_err();
+      check!(err.index == index);
+      check!(err.kind == kind);
+    }
+  }
+
+  #[test]
+  fn test_runtime_parse() -> ParseResult<()> {
+    let fmt = String::from("%Y-%m-%d %H:%M:%S");
+    check!(DateTime::parse("2012-04-21 11:00:00", &fmt)? == datetime! { 2012-04-21 11:00:00 });
+    Ok(())
+  }
+
   #[test]
   fn test_debug() {
     let dt = date::date! { 2012-04-21 }.hms(15, 0, 0).build();
@@ -0,0 +1,479 @@
+//! Parsing of date and time strings.
+//!
+//! The `strptime` crate only accepts format strings that are `&'static str`. This module provides
+//! an equivalent interpreter for format strings that are only known at runtime (for example, ones
+//! read from configuration), along with the cursor it is built on.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::DateTime;
+use crate::format::Padding;
+
+/// A result returned from date and time parsing.
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Errors occurring during parsing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+  /// An owned copy of the input string.
+  pub src: String,
+  /// The index in the input string where the error occurred.
+  pub index: Option<usize>,
+  /// A machine-readable explanation of the error.
+  pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}\n{}\n{}",
+      self.src,
+      match self.index {
+        Some(ix) => format!("{}^-----", " ".repeat(ix)),
+        None => String::new(),
+      },
+      self.kind
+    )
+  }
+}
+
+impl Error for ParseError {}
+
+/// Potential errors that occur during parsing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+  /// The input was parsed, but does not identify a single date and time (for example, an hour
+  /// greater than 12 alongside `AM` or `PM`).
+  Ambiguous,
+  /// Some date components were found, but not enough to determine the year, month, and day.
+  IncompleteDate,
+  /// The UTC offset does not agree with the time zone annotation that follows it.
+  InconsistentOffset,
+  /// A duration includes years or months, which have no fixed length.
+  InexactDuration,
+  /// Parsing finished, but characters remain in the input.
+  InputTooLong,
+  /// The input ended before parsing finished.
+  InputTooShort,
+  /// A date component, or the date it describes, is out of range.
+  InvalidDate,
+  /// The format string is not valid.
+  InvalidFormat,
+  /// A time component, or the time it describes, is out of range.
+  InvalidTime,
+  /// The input was parsed, but contained no date.
+  MissingDate,
+  /// The input does not match what was expected at this position.
+  Unexpected,
+  /// The time zone is not in the time zone database.
+  UnknownTimeZone,
+}
+
+impl fmt::Display for ParseErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", match self {
+      Self::Ambiguous => "Parsing succeeded, but the final date was ambiguous",
+      Self::IncompleteDate => "Date specified, but could not determine year, month, and day",
+      Self::InconsistentOffset => "UTC offset does not match the time zone",
+      Self::InexactDuration => "Years and months cannot be converted to an exact duration",
+      Self::InputTooLong => "Parsing finished, but input remains",
+      Self::InputTooShort => "Input terminated unexpectedly before parsing finished",
+      Self::InvalidDate => "Date components are out of range",
+      Self::InvalidFormat => "Could not parse format string",
+      Self::InvalidTime => "Time components are out of range",
+      Self::MissingDate => "Parsing successful, but no date found",
+      Self::Unexpected => "Input does not conform to format string",
+      Self::UnknownTimeZone => "Time zone is not in the time zone database",
+    })
+  }
+}
+
+/// Parse a date and time according to a `strptime`-like format string.
+pub(crate) fn parse(src: &str, fmt: &str) -> ParseResult<DateTime> {
+  let mut input = Input::new(src);
+  let mut fields = Fields::default();
+  let mut format = fmt.chars();
+  while let Some(c) = format.next() {
+    if c != '%' {
+      input.expect(c)?;
+      continue;
+    }
+
+    // Consume any modifiers, and then the specifier itself.
+    let mut padding = Padding::Default;
+    let mut digits = None;
+    let spec = loop {
+      match format.next() {
+        Some('0') => padding = Padding::Zero,
+        Some('-') => padding = Padding::Suppress,
+        Some('_' | ' ') => padding = Padding::Space,
+        Some('.') => input.expect('.')?,
+        Some(d @ ('3' | '6' | '9')) => digits = d.to_digit(10),
+        Some(spec) => break spec,
+        None => input.fail(ParseErrorKind::InvalidFormat)?,
+      }
+    };
+    if spec != 'f' && digits.is_some() {
+      input.fail(ParseErrorKind::InvalidFormat)?;
+    }
+
+    // Parse the component.
+    match spec {
+      'Y' => fields.year = Some(input.int(4, padding)? as i16),
+      'C' => fields.century = Some(input.int(2, padding)? as i16),
+      'y' => fields.year_modulo = Some(input.int(2, padding)? as i16),
+      'm' => fields.month = Some(input.int(2, padding)? as u8),
+      'b' | 'h' | 'B' => fields.month = Some(input.name(&MONTHS)? as u8 + 1),
+      'd' => fields.day = Some(input.int(2, padding)? as u8),
+      'e' => fields.day = Some(input.int(2, padding.or_space())? as u8),
+      'a' | 'A' => drop(input.name(&WEEKDAYS)?),
+      'H' => fields.hour = Some(input.int(2, padding)? as u8),
+      'k' => fields.hour = Some(input.int(2, padding.or_space())? as u8),
+      'I' => fields.hour_12 = Some(input.int(2, padding)? as u8),
+      'p' | 'P' => fields.pm = Some(input.meridiem()?),
+      'M' => fields.minute = input.int(2, padding)? as u8,
+      'S' => fields.second = input.int(2, padding)? as u8,
+      'f' => {
+        let digits = digits.unwrap_or(9);
+        fields.nanos = input.int(digits as usize, Padding::Zero)? * 10u32.pow(9 - digits);
+      },
+      'z' => fields.utc_offset = Some(input.utc_offset()?),
+      '%' => input.expect('%')?,
+      _ => input.fail(ParseErrorKind::InvalidFormat)?,
+    }
+  }
+  input.assert_consumed()?;
+  fields.build(src)
+}
+
+/// The components of a date and time, as read from a string.
+#[derive(Debug, Default)]
+pub(crate) struct Fields {
+  pub(crate) year: Option<i16>,
+  pub(crate) month: Option<u8>,
+  pub(crate) day: Option<u8>,
+  pub(crate) hour: Option<u8>,
+  pub(crate) minute: u8,
+  pub(crate) second: u8,
+  pub(crate) nanos: u32,
+  pub(crate) utc_offset: Option<i32>,
+  century: Option<i16>,
+  year_modulo: Option<i16>,
+  hour_12: Option<u8>,
+  pm: Option<u8>, // 0 or 12
+}
+
+impl Fields {
+  /// Validate the components and assemble them into a [`DateTime`].
+  pub(crate) fn build(self, src: &str) -> ParseResult<DateTime> {
+    let err = |kind| ParseError { src: src.into(), index: None, kind };
+
+    // Resolve partial years and hours.
+    let year = match (self.century, self.year_modulo) {
+      (Some(c), Some(m)) => Some(c * 100 + m),
+      (Some(_), None) => Err(err(ParseErrorKind::Ambiguous))?,
+      (None, Some(m)) => Some(if m >= 70 { 1900 + m } else { 2000 + m }),
+      (None, None) => self.year,
+    };
+    let hour = match (self.hour_12, self.pm) {
+      (Some(12), Some(pm)) => 12 - pm,
+      (Some(h @ 1..12), Some(pm)) => h + pm,
+      (None, None) => self.hour.unwrap_or_default(),
+      _ => Err(err(ParseErrorKind::Ambiguous))?,
+    };
+
+    // Validate the date and time.
+    let (year, month, day) = match (year, self.month, self.day) {
+      (Some(y), Some(m), Some(d)) => (y, m, d),
+      (None, None, None) => Err(err(ParseErrorKind::MissingDate))?,
+      _ => Err(err(ParseErrorKind::IncompleteDate))?,
+    };
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+      Err(err(ParseErrorKind::InvalidDate))?;
+    }
+    if hour >= 24 || self.minute >= 60 || self.second >= 60 || self.nanos >= 1_000_000_000 {
+      Err(err(ParseErrorKind::InvalidTime))?;
+    }
+
+    let builder =
+      DateTime::ymd(year, month, day).hms(hour, self.minute, self.second).nanos(self.nanos);
+    Ok(match self.utc_offset {
+      Some(offset) => builder.utc_offset(offset).build(),
+      None => builder.build(),
+    })
+  }
+}
+
+/// A cursor over an input string, capable of easily handling errors.
+pub(crate) struct Input<'a> {
+  src: &'a str,
+  pos: usize,
+}
+
+impl<'a> Input<'a> {
+  pub(crate) const fn new(src: &'a str) -> Self {
+    Self { src, pos: 0 }
+  }
+
+  /// The current index into the input string.
+  pub(crate) const fn pos(&self) -> usize {
+    self.pos
+  }
+
+  /// The next character, without consuming it.
+  pub(crate) fn peek(&self) -> Option<char> {
+    self.src[self.pos..].chars().next()
+  }
+
+  /// The number of consecutive ASCII digits at the current index.
+  pub(crate) fn digits_ahead(&self) -> usize {
+    self.src[self.pos..].bytes().take_while(u8::is_ascii_digit).count()
+  }
+
+  /// The input consumed since the given index.
+  pub(crate) fn since(&self, start: usize) -> &'a str {
+    &self.src[start..self.pos]
+  }
+
+  /// Consume and return the next character.
+  pub(crate) fn bump(&mut self) -> Option<char> {
+    let c = self.peek()?;
+    self.pos += c.len_utf8();
+    Some(c)
+  }
+
+  /// Consume the next character if it is the given character.
+  pub(crate) fn eat(&mut self, ch: char) -> bool {
+    let matched = self.peek() == Some(ch);
+    if matched {
+      self.pos += ch.len_utf8();
+    }
+    matched
+  }
+
+  /// Consume the given character, or fail.
+  pub(crate) fn expect(&mut self, ch: char) -> ParseResult<()> {
+    match self.peek() {
+      Some(c) if c == ch => {
+        self.pos += c.len_utf8();
+        Ok(())
+      },
+      Some(_) => self.fail(ParseErrorKind::Unexpected),
+      None => self.fail(ParseErrorKind::InputTooShort),
+    }
+  }
+
+  /// Parse an unsigned integer, usually with the given number of digits.
+  pub(crate) fn int(&mut self, width: usize, padding: Padding) -> ParseResult<u32> {
+    let start = self.pos;
+    let mut width = width;
+    if let Padding::Space = padding {
+      while width > 1 && self.eat(' ') {
+        width -= 1;
+      }
+    }
+    let mut value: u32 = 0;
+    let mut count = 0;
+    while count < width {
+      match self.peek() {
+        Some(c @ '0'..='9') => value = value * 10 + (c as u8 - b'0') as u32,
+        Some(_) if count > 0 && matches!(padding, Padding::Suppress) => break,
+        Some(_) => return self.fail(ParseErrorKind::Unexpected),
+        None if count > 0 && matches!(padding, Padding::Suppress) => break,
+        None => return Err(self.err_at(start, ParseErrorKind::InputTooShort)),
+      }
+      self.pos += 1;
+      count += 1;
+    }
+    Ok(value)
+  }
+
+  /// Parse a `+` or `-` sign.
+  pub(crate) fn sign(&mut self) -> ParseResult<i32> {
+    let sign = match self.peek() {
+      Some('+') => 1,
+      Some('-') => -1,
+      Some(_) => self.fail(ParseErrorKind::Unexpected)?,
+      None => self.fail(ParseErrorKind::InputTooShort)?,
+    };
+    self.pos += 1;
+    Ok(sign)
+  }
+
+  /// Parse a UTC offset of the form `±HHMM` or `±HH:MM`, returning it in seconds.
+  pub(crate) fn utc_offset(&mut self) -> ParseResult<i32> {
+    let start = self.pos;
+    let sign = self.sign()?;
+    let hours = self.int(2, Padding::Zero)? as i32;
+    self.eat(':');
+    let minutes = self.int(2, Padding::Zero)? as i32;
+    if hours >= 24 || minutes >= 60 {
+      return Err(self.err_at(start, ParseErrorKind::InvalidTime));
+    }
+    Ok(sign * (hours * 3_600 + minutes * 60))
+  }
+
+  /// Parse a name (such as a month or weekday), returning its index.
+  ///
+  /// This succeeds if at least the three-letter abbreviation is present (case-insensitive), and
+  /// continues to consume input until the name is completed or it ceases to find a match
+  /// (therefore matching "Sept", for example).
+  pub(crate) fn name(&mut self, names: &[&str]) -> ParseResult<usize> {
+    let rest = &self.src.as_bytes()[self.pos..];
+    if rest.len() < 3 {
+      return self.fail(ParseErrorKind::InputTooShort);
+    }
+    let Some(ix) = names.iter().position(|n| n.as_bytes()[..3].eq_ignore_ascii_case(&rest[..3]))
+    else {
+      return self.fail(ParseErrorKind::Unexpected);
+    };
+    let len =
+      (names[ix].bytes().zip(rest.iter())).take_while(|(a, b)| a.eq_ignore_ascii_case(b)).count();
+    self.pos += len;
+    Ok(ix)
+  }
+
+  /// Parse `AM` or `PM` (case-insensitive), returning the number of hours to add.
+  fn meridiem(&mut self) -> ParseResult<u8> {
+    let value = match self.peek() {
+      Some('a' | 'A') => 0,
+      Some('p' | 'P') => 12,
+      Some(_) => self.fail(ParseErrorKind::Unexpected)?,
+      None => self.fail(ParseErrorKind::InputTooShort)?,
+    };
+    self.bump();
+    if !self.eat('m') && !self.eat('M') {
+      self.fail(ParseErrorKind::Unexpected)?;
+    }
+    Ok(value)
+  }
+
+  /// Assert that no input remains, and create a parse error if it does.
+  pub(crate) fn assert_consumed(&self) -> ParseResult<()> {
+    match self.pos < self.src.len() {
+      true => self.fail(ParseErrorKind::InputTooLong),
+      false => Ok(()),
+    }
+  }
+
+  /// Generate a parse error at the current index.
+  pub(crate) fn err(&self, kind: ParseErrorKind) -> ParseError {
+    self.err_at(self.pos, kind)
+  }
+
+  /// Generate a parse error at the given index.
+  pub(crate) fn err_at(&self, index: usize, kind: ParseErrorKind) -> ParseError {
+    ParseError { src: self.src.into(), index: Some(index), kind }
+  }
+
+  pub(crate) fn fail<T>(&self, kind: ParseErrorKind) -> ParseResult<T> {
+    Err(self.err(kind))
+  }
+}
+
+impl Padding {
+  /// Use space padding unless a different padding was requested explicitly.
+  const fn or_space(self) -> Self {
+    match self {
+      Self::Default => Self::Space,
+      other => other,
+    }
+  }
+}
+
+/// Returns the number of days in the month.
+pub(crate) const fn days_in_month(year: i16, month: u8) -> u8 {
+  const DAYS_IN_MONTH: [u8; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+  let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+  match month {
+    2 if leap => 29,
+    _ => DAYS_IN_MONTH[month as usize - 1],
+  }
+}
+
+pub(crate) const MONTHS: [&str; 12] = [
+  "January", "February", "March", "April", "May", "June", "July", "August", "September",
+  "October", "November", "December",
+];
+
+pub(crate) const WEEKDAYS: [&str; 7] =
+  ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+
+  #[test]
+  fn test_parse() -> ParseResult<()> {
+    let expected = datetime! { 2012-04-21 11:00:00 };
+    for (s, fmt) in [
+      ("2012-04-21 11:00:00", "%Y-%m-%d %H:%M:%S"),
+      ("04/21/12 11:00", "%m/%d/%y %H:%M"),
+      ("21-Apr-2012 11 AM", "%d-%b-%Y %I %p"),
+      ("Saturday, April 21, 2012 at 11am", "%A, %B %-d, %C%y at %-I%P"),
+      ("2012-04-21T11:00:00.000%", "%Y-%m-%dT%H:%M:%S%.3f%%"),
+    ] {
+      check!(parse(s, fmt)? == expected, "{s}");
+    }
+    check!(parse("Fri Sept 21 2012", "%a %B %d %Y")? == datetime! { 2012-09-21 00:00:00 });
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_nanos() -> ParseResult<()> {
+    let dt = parse("2024-07-04 15:30:45.123456", "%Y-%m-%d %H:%M:%S%.6f")?;
+    check!(dt.nanosecond() == 123_456_000);
+    let dt = parse("2024-07-04 15:30:45 123456789", "%Y-%m-%d %H:%M:%S %f")?;
+    check!(dt.nanosecond() == 123_456_789);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_padding() -> ParseResult<()> {
+    let expected = datetime! { 2024-07-04 09:00:00 };
+    check!(parse("7/4/2024 9", "%-m/%-d/%Y %-H")? == expected);
+    check!(parse(" 7/ 4/2024  9", "%_m/%e/%Y %k")? == expected);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_utc_offset() -> ParseResult<()> {
+    let expected = datetime! { 2012-04-21 15:00:00 };
+    check!(parse("2012-04-21 11:00:00-0400", "%Y-%m-%d %H:%M:%S%z")? == expected);
+    check!(parse("2012-04-21 11:00:00-04:00", "%Y-%m-%d %H:%M:%S%z")? == expected);
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    for (s, fmt, index, kind) in [
+      ("2012-04-21", "%Y-%m-%d %H", Some(10), ParseErrorKind::InputTooShort),
+      ("2012-04-21 11", "%Y-%m-%d", Some(10), ParseErrorKind::InputTooLong),
+      ("2012/04/21", "%Y-%m-%d", Some(4), ParseErrorKind::Unexpected),
+      ("2012-0x-21", "%Y-%m-%d", Some(6), ParseErrorKind::Unexpected),
+      ("2012-04-21", "%Y-%m-%Q", Some(8), ParseErrorKind::InvalidFormat),
+      ("2012-04-21", "%Y-%m-%", Some(8), ParseErrorKind::InvalidFormat),
+      ("2012-04-21", "%Y-%m-%3d", Some(8), ParseErrorKind::InvalidFormat),
+      ("2012-02-30", "%Y-%m-%d", None, ParseErrorKind::InvalidDate),
+      ("2012-04-21 24:00", "%Y-%m-%d %H:%M", None, ParseErrorKind::InvalidTime),
+      ("2012-04", "%Y-%m", None, ParseErrorKind::IncompleteDate),
+      ("11:00", "%H:%M", None, ParseErrorKind::MissingDate),
+      ("2012-04-21 13 PM", "%Y-%m-%d %I %p", None, ParseErrorKind::Ambiguous),
+    ] {
+      let err = parse(s, fmt).unwrap_err();
+      check!(err.index == index, "{s} / {fmt}");
+      check!(err.kind == kind, "{s} / {fmt}");
+    }
+  }
+
+  #[test]
+  fn test_error_display() {
+    let err = parse("2012/04/21", "%Y-%m-%d").unwrap_err();
+    check!(err.to_string() == "2012/04/21\n    ^-----\nInput does not conform to format string");
+  }
+}
@@ -0,0 +1,76 @@
+//! PostgreSQL's binary formats, shared by the Diesel, sqlx, and `postgres-types` integrations.
+//!
+//! All three convert values the same way: `TIMESTAMPTZ` values are loaded in UTC, `infinity` and
+//! `-infinity` map to [`DateTime::MAX`] and [`DateTime::MIN`] (finite timestamps outside that
+//! range are an error), nanoseconds are truncated unless the value is wrapped in
+//! [`Rounded`](crate::Rounded) or [`Strict`](crate::Strict), and [`TimeInterval`]s map to
+//! `INTERVAL` as a number of microseconds, with any nanoseconds truncated toward zero.
+
+use crate::DateTime;
+use crate::Precision;
+use crate::PrecisionLoss;
+use crate::interval::TimeInterval;
+
+/// The epoch that PostgreSQL timestamps count from.
+pub(crate) const PG_EPOCH: DateTime = datetime! { 2000-01-01 00:00:00 };
+
+impl DateTime {
+  /// Convert microseconds since the PostgreSQL epoch to a date and time.
+  pub(crate) fn from_pg_timestamp(micros: i64) -> Result<Self, String> {
+    match micros {
+      i64::MAX => Ok(DateTime::MAX),
+      i64::MIN => Ok(DateTime::MIN),
+      micros => match PG_EPOCH + TimeInterval::from_microseconds(micros) {
+        dt if dt < DateTime::MIN || dt > DateTime::MAX =>
+          Err(format!("{dt:?} is outside the range of DateTime")),
+        dt => Ok(dt),
+      },
+    }
+  }
+
+  /// Mark this date and time as UTC, as `TIMESTAMPTZ` values are loaded.
+  #[cfg_attr(not(feature = "tz"), allow(unused_mut))]
+  pub(crate) fn pg_utc(mut self) -> Self {
+    #[cfg(feature = "tz")]
+    {
+      self.tz = crate::tz::UTC;
+    }
+    self
+  }
+
+  /// Convert this date and time to microseconds since the PostgreSQL epoch, following `policy` for
+  /// any nanoseconds.
+  pub(crate) fn pg_timestamp(&self, policy: PrecisionLoss) -> Result<i64, String> {
+    if *self >= DateTime::MAX {
+      return Ok(i64::MAX);
+    } else if *self <= DateTime::MIN {
+      return Ok(i64::MIN);
+    }
+    Ok(match self.to_storage_precision(Precision::Microsecond, policy)? {
+      // Rounding up may pass the end of the supported range.
+      dt if dt > DateTime::MAX => i64::MAX,
+      dt => (dt - PG_EPOCH).as_microseconds(),
+    })
+  }
+}
+
+impl TimeInterval {
+  /// Convert the components of a PostgreSQL interval to a time interval, counting each day as 24
+  /// hours.
+  pub(crate) fn from_pg_interval(
+    microseconds: i64, days: i32, months: i32,
+  ) -> Result<Self, String> {
+    if months != 0 {
+      return Err(format!("Cannot convert an interval of {months} months to a TimeInterval"));
+    }
+    let interval = TimeInterval::from_microseconds(microseconds);
+    Ok(TimeInterval::new(interval.seconds() + i64::from(days) * 86_400, interval.nanoseconds()))
+  }
+
+  /// Convert this time interval to a number of microseconds, truncating any nanoseconds toward
+  /// zero.
+  pub(crate) fn pg_microseconds(&self) -> Result<i64, String> {
+    i64::try_from(self.as_nanoseconds() / 1_000)
+      .map_err(|_| format!("{self:?} is too large for a PostgreSQL interval"))
+  }
+}
@@ -0,0 +1,161 @@
+//! Serialization to/from PostgreSQL using `postgres-types`
+//!
+//! Values are converted following [PostgreSQL's conventions](crate::pg). `TIMESTAMP` columns are
+//! written with the UTC wall clock time.
+
+use std::error::Error;
+
+use bytes::BufMut;
+use bytes::BytesMut;
+use postgres_types::FromSql;
+use postgres_types::IsNull;
+use postgres_types::ToSql;
+use postgres_types::Type;
+use postgres_types::accepts;
+use postgres_types::to_sql_checked;
+
+use crate::DateTime;
+use crate::Rounded;
+use crate::Strict;
+use crate::interval::TimeInterval;
+use crate::storage::Stored;
+
+type BoxError = Box<dyn Error + Sync + Send>;
+
+/// Implement `ToSql` for `TIMESTAMP` and `TIMESTAMPTZ`, following each type's precision-loss
+/// policy.
+macro_rules! to_sql {
+  ($($ty:ty),*) => {$(
+    impl ToSql for $ty {
+      accepts!(TIMESTAMP, TIMESTAMPTZ);
+
+      to_sql_checked!();
+
+      fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        let (dt, policy) = self.stored();
+        out.put_i64(dt.pg_timestamp(policy)?);
+        Ok(IsNull::No)
+      }
+    }
+  )*};
+}
+
+to_sql!(DateTime, Rounded, Strict);
+
+impl<'a> FromSql<'a> for DateTime {
+  accepts!(TIMESTAMP, TIMESTAMPTZ);
+
+  fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+    let micros = raw.try_into().map_err(|_| "Invalid timestamp length")?;
+    let dt = DateTime::from_pg_timestamp(i64::from_be_bytes(micros))?;
+    Ok(if *ty == Type::TIMESTAMPTZ { dt.pg_utc() } else { dt })
+  }
+}
+
+impl ToSql for TimeInterval {
+  accepts!(INTERVAL);
+
+  to_sql_checked!();
+
+  fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+    out.put_i64(self.pg_microseconds()?);
+    out.put_i32(0); // days
+    out.put_i32(0); // months
+    Ok(IsNull::No)
+  }
+}
+
+impl<'a> FromSql<'a> for TimeInterval {
+  accepts!(INTERVAL);
+
+  fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+    if raw.len() != 16 {
+      return Err("Invalid interval length".into());
+    }
+    let microseconds = i64::from_be_bytes(raw[..8].try_into()?);
+    let days = i32::from_be_bytes(raw[8..12].try_into()?);
+    let months = i32::from_be_bytes(raw[12..].try_into()?);
+    Ok(TimeInterval::from_pg_interval(microseconds, days, months)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+  use crate::time_interval;
+
+  fn encode(value: &(dyn ToSql + Sync), ty: &Type) -> Result<Vec<u8>, BoxError> {
+    let mut out = BytesMut::new();
+    value.to_sql_checked(ty, &mut out)?;
+    Ok(out.to_vec())
+  }
+
+  #[test]
+  fn test_timestamp() -> Result<(), BoxError> {
+    for dt in [
+      datetime! { 2012-04-21 15:00:00 },
+      datetime! { 2000-01-01 00:00:00 },
+      datetime! { 1969-12-31 23:59:59.999999 },
+      DateTime::MAX,
+      DateTime::MIN,
+    ] {
+      for ty in [Type::TIMESTAMP, Type::TIMESTAMPTZ] {
+        check!(DateTime::from_sql(&ty, &encode(&dt, &ty)?)? == dt);
+      }
+    }
+    check!(
+      encode(&datetime! { 2000-01-01 00:00:01 }, &Type::TIMESTAMP)? == 1_000_000_i64.to_be_bytes()
+    );
+    check!(encode(&DateTime::MAX, &Type::TIMESTAMPTZ)? == i64::MAX.to_be_bytes());
+    check!(encode(&DateTime::MIN, &Type::TIMESTAMPTZ)? == i64::MIN.to_be_bytes());
+    check!(encode(&datetime! { 2012-04-21 15:00:00 }, &Type::DATE).is_err());
+    check!(DateTime::from_sql(&Type::TIMESTAMP, &[0; 4]).is_err());
+    check!(<DateTime as FromSql>::accepts(&Type::TIMESTAMPTZ));
+    check!(!<DateTime as FromSql>::accepts(&Type::INTERVAL));
+    Ok(())
+  }
+
+  #[test]
+  fn test_timestamp_policy() -> Result<(), BoxError> {
+    let dt = datetime! { 2000-01-01 00:00:00.0000035 };
+    check!(encode(&dt, &Type::TIMESTAMP)? == 3_i64.to_be_bytes());
+    check!(encode(&Rounded(dt), &Type::TIMESTAMPTZ)? == 4_i64.to_be_bytes());
+    check!(encode(&Strict(dt), &Type::TIMESTAMP).is_err());
+    check!(encode(&Strict(dt), &Type::DATE).is_err());
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_timestamptz_utc() -> Result<(), BoxError> {
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    let bytes = encode(&dt, &Type::TIMESTAMPTZ)?;
+    let loaded = DateTime::from_sql(&Type::TIMESTAMPTZ, &bytes)?;
+    check!(loaded == dt);
+    check!(loaded.tz_name() == Some("UTC"));
+    check!(DateTime::from_sql(&Type::TIMESTAMP, &bytes)?.tz_name() == None);
+    Ok(())
+  }
+
+  #[test]
+  fn test_interval() -> Result<(), BoxError> {
+    for interval in [time_interval!(0s), time_interval!(1h 30m), time_interval!(-10d 0.000001s)] {
+      check!(
+        TimeInterval::from_sql(&Type::INTERVAL, &encode(&interval, &Type::INTERVAL)?)? == interval
+      );
+    }
+    let bytes = encode(&TimeInterval::new(3_600, 123_456_789), &Type::INTERVAL)?;
+    check!(bytes == [&3_600_123_456_i64.to_be_bytes()[..], &[0; 8]].concat());
+    check!(encode(&TimeInterval::new(i64::MAX, 0), &Type::INTERVAL).is_err());
+
+    // Days count as 24 hours, but months are an error.
+    let bytes = [&0_i64.to_be_bytes()[..], &1_i32.to_be_bytes(), &0_i32.to_be_bytes()].concat();
+    check!(TimeInterval::from_sql(&Type::INTERVAL, &bytes)? == time_interval!(1d));
+    let bytes = [&0_i64.to_be_bytes()[..], &0_i32.to_be_bytes(), &1_i32.to_be_bytes()].concat();
+    check!(TimeInterval::from_sql(&Type::INTERVAL, &bytes).is_err());
+    check!(TimeInterval::from_sql(&Type::INTERVAL, &[0; 8]).is_err());
+    Ok(())
+  }
+}
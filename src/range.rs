@@ -0,0 +1,222 @@
+//! Ranges of time between two [`DateTime`]s.
+//!
+//! A [`DateTimeRange`] is a span of time whose ends may each be inclusive, exclusive, or
+//! unbounded, such as a reservation window. It corresponds to PostgreSQL's `tstzrange` and
+//! `tsrange` types.
+
+use std::ops::Bound;
+use std::ops::Range;
+use std::ops::RangeBounds;
+use std::ops::RangeFrom;
+use std::ops::RangeFull;
+use std::ops::RangeInclusive;
+use std::ops::RangeTo;
+use std::ops::RangeToInclusive;
+
+use crate::DateTime;
+
+/// A range of time, whose ends may each be inclusive, exclusive, or unbounded.
+///
+/// Ranges can be created from Rust's range syntax, or from explicit bounds with
+/// [`DateTimeRange::new`].
+///
+/// ## Examples
+///
+/// ```
+/// use std::ops::Bound;
+///
+/// use datetime::datetime;
+/// use datetime::range::DateTimeRange;
+///
+/// let window =
+///   DateTimeRange::from(datetime! { 2012-04-21 09:00:00 }..datetime! { 2012-04-21 17:00:00 });
+/// assert!(window.contains(&datetime! { 2012-04-21 09:00:00 }));
+/// assert!(!window.contains(&datetime! { 2012-04-21 17:00:00 }));
+///
+/// let after =
+///   DateTimeRange::new(Bound::Excluded(datetime! { 2012-04-21 12:00:00 }), Bound::Unbounded);
+/// assert!(window.overlaps(&after));
+/// ```
+#[derive(Clone, Copy, Debug, Eq)]
+#[cfg_attr(
+  feature = "diesel-pg",
+  derive(diesel::AsExpression, diesel::FromSqlRow),
+  diesel(sql_type = diesel::sql_types::Tstzrange),
+  diesel(sql_type = diesel::sql_types::Tsrange)
+)]
+pub struct DateTimeRange {
+  start: Bound<DateTime>,
+  end: Bound<DateTime>,
+}
+
+impl DateTimeRange {
+  /// A range that contains no time at all.
+  pub const EMPTY: Self = Self::new(
+    Bound::Excluded(DateTime::from_timestamp(0, 0)),
+    Bound::Excluded(DateTime::from_timestamp(0, 0)),
+  );
+
+  /// Create a new range from its start and end bounds.
+  pub const fn new(start: Bound<DateTime>, end: Bound<DateTime>) -> Self {
+    Self { start, end }
+  }
+
+  /// The start of this range.
+  pub const fn start(&self) -> Bound<DateTime> {
+    self.start
+  }
+
+  /// The end of this range.
+  pub const fn end(&self) -> Bound<DateTime> {
+    self.end
+  }
+
+  /// Whether this range contains no time at all.
+  pub fn is_empty(&self) -> bool {
+    match (self.start, self.end) {
+      (Bound::Included(start), Bound::Included(end)) => start > end,
+      (Bound::Included(start) | Bound::Excluded(start), Bound::Excluded(end))
+      | (Bound::Excluded(start), Bound::Included(end)) => start >= end,
+      (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+    }
+  }
+
+  /// Whether the given date and time falls within this range.
+  pub fn contains(&self, dt: &DateTime) -> bool {
+    RangeBounds::contains(self, dt)
+  }
+
+  /// Whether this range and `other` have any time in common.
+  pub fn overlaps(&self, other: &DateTimeRange) -> bool {
+    !self.is_empty()
+      && !other.is_empty()
+      && starts_before_end(self.start, other.end)
+      && starts_before_end(other.start, self.end)
+  }
+}
+
+/// Whether a range starting at `start` has any time before a range ending at `end` ends.
+fn starts_before_end(start: Bound<DateTime>, end: Bound<DateTime>) -> bool {
+  match (start, end) {
+    (Bound::Included(start), Bound::Included(end)) => start <= end,
+    (Bound::Included(start) | Bound::Excluded(start), Bound::Excluded(end))
+    | (Bound::Excluded(start), Bound::Included(end)) => start < end,
+    (Bound::Unbounded, _) | (_, Bound::Unbounded) => true,
+  }
+}
+
+impl PartialEq for DateTimeRange {
+  /// Ranges are equal if they have the same bounds, or are both empty.
+  fn eq(&self, other: &Self) -> bool {
+    (self.start == other.start && self.end == other.end) || (self.is_empty() && other.is_empty())
+  }
+}
+
+impl RangeBounds<DateTime> for DateTimeRange {
+  fn start_bound(&self) -> Bound<&DateTime> {
+    self.start.as_ref()
+  }
+
+  fn end_bound(&self) -> Bound<&DateTime> {
+    self.end.as_ref()
+  }
+}
+
+impl From<(Bound<DateTime>, Bound<DateTime>)> for DateTimeRange {
+  fn from((start, end): (Bound<DateTime>, Bound<DateTime>)) -> Self {
+    Self::new(start, end)
+  }
+}
+
+impl From<Range<DateTime>> for DateTimeRange {
+  fn from(range: Range<DateTime>) -> Self {
+    Self::new(Bound::Included(range.start), Bound::Excluded(range.end))
+  }
+}
+
+impl From<RangeInclusive<DateTime>> for DateTimeRange {
+  fn from(range: RangeInclusive<DateTime>) -> Self {
+    let (start, end) = range.into_inner();
+    Self::new(Bound::Included(start), Bound::Included(end))
+  }
+}
+
+impl From<RangeFrom<DateTime>> for DateTimeRange {
+  fn from(range: RangeFrom<DateTime>) -> Self {
+    Self::new(Bound::Included(range.start), Bound::Unbounded)
+  }
+}
+
+impl From<RangeTo<DateTime>> for DateTimeRange {
+  fn from(range: RangeTo<DateTime>) -> Self {
+    Self::new(Bound::Unbounded, Bound::Excluded(range.end))
+  }
+}
+
+impl From<RangeToInclusive<DateTime>> for DateTimeRange {
+  fn from(range: RangeToInclusive<DateTime>) -> Self {
+    Self::new(Bound::Unbounded, Bound::Included(range.end))
+  }
+}
+
+impl From<RangeFull> for DateTimeRange {
+  fn from(_: RangeFull) -> Self {
+    Self::new(Bound::Unbounded, Bound::Unbounded)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+
+  fn at(hour: u8) -> DateTime {
+    DateTime::ymd(2012, 4, 21).hms(hour, 0, 0).build()
+  }
+
+  #[test]
+  fn test_contains() {
+    let range = DateTimeRange::from(at(9)..at(17));
+    check!(!range.contains(&at(8)));
+    check!(range.contains(&at(9)));
+    check!(range.contains(&at(16)));
+    check!(!range.contains(&at(17)));
+
+    let range = DateTimeRange::from(at(9)..=at(17));
+    check!(range.contains(&at(17)));
+    check!(!range.contains(&at(18)));
+
+    let range = DateTimeRange::new(Bound::Excluded(at(9)), Bound::Unbounded);
+    check!(!range.contains(&at(9)));
+    check!(range.contains(&DateTime::MAX));
+    check!(DateTimeRange::from(..).contains(&DateTime::MIN));
+    check!(!DateTimeRange::EMPTY.contains(&DateTime::from_timestamp(0, 0)));
+  }
+
+  #[test]
+  fn test_is_empty() {
+    check!(DateTimeRange::EMPTY.is_empty());
+    check!(DateTimeRange::from(at(9)..at(9)).is_empty());
+    check!(!DateTimeRange::from(at(9)..=at(9)).is_empty());
+    check!(DateTimeRange::from(at(10)..=at(9)).is_empty());
+    check!(DateTimeRange::new(Bound::Excluded(at(9)), Bound::Included(at(9))).is_empty());
+    check!(!DateTimeRange::from(..at(9)).is_empty());
+    check!(DateTimeRange::from(at(9)..at(9)) == DateTimeRange::EMPTY);
+    check!(DateTimeRange::from(at(9)..at(10)) != DateTimeRange::EMPTY);
+  }
+
+  #[test]
+  fn test_overlaps() {
+    let range = DateTimeRange::from(at(9)..at(17));
+    check!(range.overlaps(&DateTimeRange::from(at(16)..at(18))));
+    check!(range.overlaps(&DateTimeRange::from(at(8)..=at(9))));
+    check!(!range.overlaps(&DateTimeRange::from(at(17)..at(18))));
+    check!(!range.overlaps(&DateTimeRange::from(at(7)..at(9))));
+    check!(range.overlaps(&DateTimeRange::from(..)));
+    check!(range.overlaps(&DateTimeRange::from(..=at(9))));
+    check!(!range.overlaps(&DateTimeRange::from(..at(9))));
+    check!(!range.overlaps(&DateTimeRange::EMPTY));
+    check!(!DateTimeRange::from(..).overlaps(&DateTimeRange::EMPTY));
+  }
+}
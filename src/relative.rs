@@ -0,0 +1,814 @@
+//! Natural-language dates and times relative to a reference, such as "tomorrow at 3pm".
+
+use date::Date;
+use date::interval::DateInterval;
+
+use crate::DateTime;
+use crate::FromDate;
+use crate::ParseError;
+use crate::ParseErrorKind;
+use crate::ParseResult;
+use crate::interval::TimeInterval;
+use crate::parse::MONTHS;
+use crate::parse::WEEKDAYS;
+use crate::parse::days_in_month;
+use crate::tz::TimeZoneRef;
+
+/// A date and time described relative to a reference, as returned by
+/// [`DateTime::parse_relative`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RelativeDateTime {
+  /// A single instant, such as "tomorrow at 3pm" or "in 2 hours".
+  Instant(DateTime),
+  /// A span of time, such as "tomorrow" or "next week".
+  ///
+  /// The start is inclusive and the end is exclusive.
+  Span { start: DateTime, end: DateTime },
+}
+
+impl RelativeDateTime {
+  /// The instant, or the beginning of the span.
+  pub const fn start(&self) -> DateTime {
+    match self {
+      Self::Instant(dt) => *dt,
+      Self::Span { start, .. } => *start,
+    }
+  }
+
+  /// The instant, or the (exclusive) end of the span.
+  pub const fn end(&self) -> DateTime {
+    match self {
+      Self::Instant(dt) => *dt,
+      Self::Span { end, .. } => *end,
+    }
+  }
+}
+
+impl DateTime {
+  /// Parse a natural-language date and time, relative to the given reference and interpreted in
+  /// the given time zone.
+  ///
+  /// Phrases that name an instant return [`RelativeDateTime::Instant`]:
+  ///
+  /// - `now`
+  /// - Offsets: `in 2 hours`, `3 days ago`, `an hour and 30 minutes from now`, or the
+  ///   [`time_interval!`](crate::time_interval) language: `in 2h 30m`, `1d ago`
+  /// - Days with times: `tomorrow at 3pm`, `next Friday at 9:30`, `15:45`, `noon yesterday`
+  /// - Boundaries: `end of month`, `start of next week`, `beginning of the year`
+  ///
+  /// Phrases that name a whole day or period return [`RelativeDateTime::Span`]:
+  ///
+  /// - Days: `today`, `tomorrow`, `next Friday`, `last Monday of June`, `first Tuesday of next
+  ///   month`
+  /// - Periods: `this week`, `next month`, `last year`
+  ///
+  /// Seconds, minutes, and hours are exact durations; days, weeks, months, and years move the
+  /// calendar while keeping the wall clock time. Weeks begin on Monday. A bare weekday (or `this
+  /// Friday`) is the next such day on or after the reference date, `next Friday` is the next one
+  /// strictly after it, and `last Friday` is the most recent one strictly before it. A month name
+  /// without a year refers to the reference date's year.
+  ///
+  /// The result is in the given time zone. Matching is case-insensitive.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::DateTime;
+  /// use datetime::RelativeDateTime;
+  /// use datetime::datetime;
+  /// use datetime::tz::us;
+  ///
+  /// let reference = datetime! { 2024-07-04 09:00:00 us::EASTERN };
+  /// let dt = DateTime::parse_relative("tomorrow at 3pm", reference, us::EASTERN).unwrap();
+  /// assert_eq!(dt, RelativeDateTime::Instant(datetime! { 2024-07-05 15:00:00 us::EASTERN }));
+  /// ```
+  pub fn parse_relative(
+    input: impl AsRef<str>, reference: DateTime, tz: TimeZoneRef<'static>,
+  ) -> ParseResult<RelativeDateTime> {
+    Relative::new(input.as_ref(), reference, tz).parse()
+  }
+}
+
+/// An hour, minute, and second.
+type Hms = (u8, u8, u8);
+
+/// A period of the calendar.
+#[derive(Clone, Copy)]
+enum Period {
+  Day,
+  Week,
+  Month,
+  Year,
+}
+
+/// A movement forward or backward from the reference.
+#[derive(Default)]
+struct Shift {
+  months: i32,
+  days: i32,
+  nanos: i128,
+}
+
+impl Shift {
+  /// This shift multiplied by `mantissa / scale`, or the kind of error if that is out of range.
+  fn scaled(&self, mantissa: i128, scale: i128) -> Result<Self, ParseErrorKind> {
+    let calendar = |n: i32| {
+      (n as i128)
+        .checked_mul(mantissa)
+        .and_then(|n| i32::try_from(n).ok())
+        .ok_or(ParseErrorKind::InvalidDate)
+    };
+    Ok(Self {
+      months: calendar(self.months)?,
+      days: calendar(self.days)?,
+      nanos: self.nanos.checked_mul(mantissa).ok_or(ParseErrorKind::InvalidTime)? / scale,
+    })
+  }
+
+  /// The sum of two shifts, or the kind of error if that is out of range.
+  fn checked_add(&self, other: &Self) -> Result<Self, ParseErrorKind> {
+    Ok(Self {
+      months: self.months.checked_add(other.months).ok_or(ParseErrorKind::InvalidDate)?,
+      days: self.days.checked_add(other.days).ok_or(ParseErrorKind::InvalidDate)?,
+      nanos: self.nanos.checked_add(other.nanos).ok_or(ParseErrorKind::InvalidTime)?,
+    })
+  }
+}
+
+/// A lowercase word of input, and where it began.
+struct Word {
+  start: usize,
+  text: String,
+}
+
+/// Parser state for a relative date and time.
+struct Relative<'a> {
+  src: &'a str,
+  words: Vec<Word>,
+  ix: usize,
+  reference: DateTime,
+  today: Date,
+  tz: TimeZoneRef<'static>,
+}
+
+impl<'a> Relative<'a> {
+  fn new(src: &'a str, reference: DateTime, tz: TimeZoneRef<'static>) -> Self {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (ix, c) in src.char_indices().chain([(src.len(), ' ')]) {
+      match (c.is_whitespace() || c == ',', start) {
+        (true, Some(s)) => {
+          words.push(Word { start: s, text: src[s..ix].to_lowercase() });
+          start = None;
+        },
+        (false, None) => start = Some(ix),
+        _ => {},
+      }
+    }
+    let reference = reference.with_tz(tz);
+    Self { src, words, ix: 0, reference, today: reference.date(), tz }
+  }
+
+  fn parse(mut self) -> ParseResult<RelativeDateTime> {
+    let result = match self.peek() {
+      None => self.fail(ParseErrorKind::InputTooShort)?,
+      Some("now") => {
+        self.ix += 1;
+        RelativeDateTime::Instant(self.reference)
+      },
+      Some("in") => {
+        self.ix += 1;
+        let start = self.start();
+        let shift = self.duration()?;
+        RelativeDateTime::Instant(self.shifted(shift, 1, start)?)
+      },
+      Some("start" | "beginning" | "end") => self.boundary()?,
+      Some("this" | "next" | "last") if self.period_at(self.ix + 1).is_some() => {
+        let shift = self.relation()?;
+        let period = self.period_at(self.ix).expect("checked above");
+        self.ix += 1;
+        let (start, end) = self.bounds(period, shift)?;
+        RelativeDateTime::Span { start: self.midnight(start)?, end: self.midnight(end)? }
+      },
+      Some(_) if self.duration_ahead() => {
+        let start = self.start();
+        let shift = self.duration()?;
+        let sign = match self.peek() {
+          Some("ago") => {
+            self.ix += 1;
+            -1
+          },
+          Some("from") => {
+            self.ix += 1;
+            self.expect("now")?;
+            1
+          },
+          _ => self.fail(self.missing())?,
+        };
+        RelativeDateTime::Instant(self.shifted(shift, sign, start)?)
+      },
+      Some(_) => {
+        let mut time = self.time()?;
+        let day = self.day()?;
+        if time.is_none() && day.is_some() {
+          time = self.time()?;
+        }
+        match (day, time) {
+          (Some(day), None) => RelativeDateTime::Span {
+            start: self.midnight(day)?,
+            end: self.midnight(day + DateInterval::new(1))?,
+          },
+          (day, Some((start, hms))) =>
+            RelativeDateTime::Instant(self.at(day.unwrap_or(self.today), hms, 0, start)?),
+          (None, None) => self.fail(ParseErrorKind::Unexpected)?,
+        }
+      },
+    };
+    match self.peek() {
+      Some(_) => self.fail(ParseErrorKind::InputTooLong),
+      None => Ok(result),
+    }
+  }
+
+  /// Parse a day: `today`, `next Friday`, `last Monday of June`, and so on.
+  fn day(&mut self) -> ParseResult<Option<Date>> {
+    self.eat("on");
+    let Some(word) = self.peek() else {
+      return Ok(None);
+    };
+    let today = self.today;
+    let date = match word {
+      "today" => today,
+      "tomorrow" => today + DateInterval::new(1),
+      "yesterday" => today - DateInterval::new(1),
+      "this" | "next" | "last" if self.weekday_at(self.ix + 1).is_some() => {
+        let ordinal =
+          self.word_at(self.ix + 2) == Some("of") || self.word_at(self.ix + 2) == Some("in");
+        if word == "last" && ordinal {
+          return self.ordinal_weekday().map(Some);
+        }
+        let relation = self.relation()?;
+        let weekday = self.weekday_at(self.ix).expect("checked above");
+        let days = (weekday + 7 - today.weekday() as i32) % 7;
+        match relation {
+          0 => today + DateInterval::new(days),
+          1 => today + DateInterval::new(if days == 0 { 7 } else { days }),
+          _ => today - DateInterval::new(if days == 0 { 7 } else { 7 - days }),
+        }
+      },
+      _ if self.weekday_at(self.ix).is_some() => {
+        let weekday = self.weekday_at(self.ix).expect("checked above");
+        today + DateInterval::new((weekday + 7 - today.weekday() as i32) % 7)
+      },
+      _ if ordinal(word).is_some() => return self.ordinal_weekday().map(Some),
+      _ => return Ok(None),
+    };
+    self.ix += 1;
+    Ok(Some(date))
+  }
+
+  /// Parse `first Tuesday of March`, `last Friday of next month`, and so on.
+  fn ordinal_weekday(&mut self) -> ParseResult<Date> {
+    let start = self.start();
+    let Some(n) = self.peek().and_then(ordinal) else {
+      return self.fail(ParseErrorKind::Unexpected);
+    };
+    self.ix += 1;
+    let Some(weekday) = self.weekday_at(self.ix) else {
+      return self.fail(self.missing());
+    };
+    self.ix += 1;
+    if !self.eat("of") {
+      self.expect("in")?;
+    }
+    let first = match self.peek() {
+      Some("this" | "next" | "last") => {
+        let shift = self.relation()?;
+        self.expect("month")?;
+        self.bounds(Period::Month, shift)?.0
+      },
+      Some(_) => {
+        let Some(month) = name_index(&MONTHS, self.peek()) else {
+          return self.fail(ParseErrorKind::Unexpected);
+        };
+        self.ix += 1;
+        let year = match self.peek().map(str::parse::<i16>) {
+          Some(Ok(year)) => {
+            self.ix += 1;
+            year
+          },
+          _ => self.today.year(),
+        };
+        Date::new(year, month as u8 + 1, 1)
+      },
+      None => self.fail(ParseErrorKind::InputTooShort)?,
+    };
+    let last = days_in_month(first.year(), first.month()) as i32;
+    let offset = (weekday + 7 - first.weekday() as i32) % 7;
+    let day = match n {
+      0 => offset + 1 + (last - offset - 1) / 7 * 7,
+      n => offset + 1 + (n - 1) * 7,
+    };
+    if day > last {
+      return Err(self.err_at(start, ParseErrorKind::InvalidDate));
+    }
+    Ok(first + DateInterval::new(day - 1))
+  }
+
+  /// Parse an optional time of day, returning where it began and its hour, minute, and second.
+  fn time(&mut self) -> ParseResult<Option<(usize, Hms)>> {
+    let at = self.eat("at");
+    let Some(word) = self.peek() else {
+      return match at {
+        true => self.fail(ParseErrorKind::InputTooShort),
+        false => Ok(None),
+      };
+    };
+    let start = self.start();
+    let hms = match word {
+      "noon" => (12, 0, 0),
+      "midnight" => (0, 0, 0),
+      _ => {
+        let (body, mut meridiem) = match word.strip_suffix("am").or(word.strip_suffix("pm")) {
+          Some(body) => (body, Some(word.ends_with("pm"))),
+          None => (word, None),
+        };
+        let parts = body.split(':').map(|p| match p.len() {
+          1 | 2 if p.bytes().all(|b| b.is_ascii_digit()) => p.parse::<u8>().ok(),
+          _ => None,
+        });
+        let parts = parts.collect::<Option<Vec<_>>>().filter(|p| p.len() <= 3);
+        let Some(parts) = parts else {
+          return match at {
+            true => self.fail(ParseErrorKind::Unexpected),
+            false => Ok(None),
+          };
+        };
+        if meridiem.is_none() && matches!(self.word_at(self.ix + 1), Some("am" | "pm")) {
+          meridiem = Some(self.word_at(self.ix + 1) == Some("pm"));
+          self.ix += 1;
+        }
+        if !at && meridiem.is_none() && parts.len() == 1 {
+          return Ok(None);
+        }
+        let (hour, minute, second) = (parts[0], parts.get(1), parts.get(2));
+        let (minute, second) = (minute.copied().unwrap_or(0), second.copied().unwrap_or(0));
+        let hour = match meridiem {
+          Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+          None if hour < 24 => hour,
+          _ => 24,
+        };
+        if hour >= 24 || minute >= 60 || second >= 60 {
+          return Err(self.err_at(start, ParseErrorKind::InvalidTime));
+        }
+        (hour, minute, second)
+      },
+    };
+    self.ix += 1;
+    Ok(Some((start, hms)))
+  }
+
+  /// Parse `start of next week`, `end of the month`, and so on.
+  fn boundary(&mut self) -> ParseResult<RelativeDateTime> {
+    let end = self.peek() == Some("end");
+    self.ix += 1;
+    self.expect("of")?;
+    self.eat("the");
+    let shift = match self.peek() {
+      Some("this" | "next" | "last") => self.relation()?,
+      _ => 0,
+    };
+    let Some(period) = self.period_at(self.ix) else {
+      return self.fail(self.missing());
+    };
+    self.ix += 1;
+    let (start, next) = self.bounds(period, shift)?;
+    Ok(RelativeDateTime::Instant(match end {
+      true => self.midnight(next)? - TimeInterval::new(0, 1),
+      false => self.midnight(start)?,
+    }))
+  }
+
+  /// Parse a duration: `2 hours`, `a day and 30 minutes`, `1d 12h`, and so on.
+  fn duration(&mut self) -> ParseResult<Shift> {
+    let mut shift = Shift::default();
+    let mut any = false;
+    loop {
+      if any && self.word_at(self.ix) == Some("and") {
+        self.ix += 1;
+      }
+      let Some(word) = self.peek() else { break };
+      let start = self.start();
+      if let Some(compact) = compact(word) {
+        shift = compact.and_then(|c| shift.checked_add(&c)).map_err(|k| self.err_at(start, k))?;
+        self.ix += 1;
+        any = true;
+        continue;
+      }
+      let Some((mantissa, scale)) = quantity(word) else { break };
+      self.ix += 1;
+      let Some(unit) = self.peek().and_then(unit) else {
+        return self.fail(self.missing());
+      };
+      if scale != 1 && (unit.months != 0 || unit.days != 0) {
+        return Err(self.err_at(start, ParseErrorKind::Unexpected));
+      }
+      shift = unit
+        .scaled(mantissa, scale)
+        .and_then(|u| shift.checked_add(&u))
+        .map_err(|k| self.err_at(start, k))?;
+      self.ix += 1;
+      any = true;
+    }
+    match any {
+      true => Ok(shift),
+      false => self.fail(self.missing()),
+    }
+  }
+
+  /// Whether a duration begins at the current word.
+  fn duration_ahead(&self) -> bool {
+    let Some(word) = self.peek() else { return false };
+    compact(word).is_some()
+      || quantity(word).is_some() && self.word_at(self.ix + 1).and_then(unit).is_some()
+  }
+
+  /// Parse `this`, `next`, or `last`, returning the number of periods to move.
+  fn relation(&mut self) -> ParseResult<i32> {
+    let shift = match self.peek() {
+      Some("this") => 0,
+      Some("next") => 1,
+      Some("last") => -1,
+      _ => self.fail(self.missing())?,
+    };
+    self.ix += 1;
+    Ok(shift)
+  }
+
+  /// Apply a shift (parsed from the duration beginning at `start`) to the reference, in the given
+  /// direction.
+  fn shifted(&self, shift: Shift, sign: i32, start: usize) -> ParseResult<DateTime> {
+    let mut dt = self.reference;
+    if shift.months != 0 || shift.days != 0 {
+      let date = add_months(self.today, shift.months * sign)
+        .map(|d| d.timestamp() + (shift.days * sign) as i64 * 86_400)
+        .filter(|ts| (Date::MIN.timestamp()..=Date::MAX.timestamp()).contains(ts))
+        .ok_or_else(|| self.err_at(start, ParseErrorKind::InvalidDate))?;
+      let hms = (dt.hour(), dt.minute(), dt.second());
+      dt = self.at(Date::from_timestamp(date), hms, dt.nanosecond(), start)?;
+    }
+    let (min, max) = (DateTime::MIN.as_nanoseconds(), DateTime::MAX.as_nanoseconds());
+    let nanos = dt.as_nanoseconds().checked_add(shift.nanos * sign as i128);
+    match nanos.filter(|n| (min..=max).contains(n)) {
+      Some(nanos) => Ok(dt + TimeInterval::from_nanoseconds(nanos - dt.as_nanoseconds())),
+      None => Err(self.err_at(start, ParseErrorKind::InvalidTime)),
+    }
+  }
+
+  /// The first day of the period containing the reference date (moved by `shift` periods), and
+  /// the first day of the period after that. Called just after consuming the name of the period.
+  fn bounds(&self, period: Period, shift: i32) -> ParseResult<(Date, Date)> {
+    let today = self.today;
+    let bounds = match period {
+      Period::Day => {
+        let start = today + DateInterval::new(shift);
+        Some((start, start + DateInterval::new(1)))
+      },
+      Period::Week => {
+        let monday = today - DateInterval::new((today.weekday() as i32 + 6) % 7);
+        let start = monday + DateInterval::new(shift * 7);
+        Some((start, start + DateInterval::new(7)))
+      },
+      Period::Month => add_months(Date::new(today.year(), today.month(), 1), shift)
+        .and_then(|start| Some((start, add_months(start, 1)?))),
+      Period::Year => i16::try_from(shift)
+        .ok()
+        .and_then(|shift| today.year().checked_add(shift))
+        .and_then(|year| Some((Date::new(year, 1, 1), Date::new(year.checked_add(1)?, 1, 1)))),
+    };
+    bounds.ok_or_else(|| self.err_at(self.words[self.ix - 1].start, ParseErrorKind::InvalidDate))
+  }
+
+  /// The given wall clock date and time in the time zone.
+  fn at(&self, date: Date, hms: Hms, nanos: u32, start: usize) -> ParseResult<DateTime> {
+    match date.hms(hms.0, hms.1, hms.2).nanos(nanos).tz(self.tz).map(|b| b.build()) {
+      Ok(dt) if (DateTime::MIN..=DateTime::MAX).contains(&dt) => Ok(dt),
+      _ => Err(self.err_at(start, ParseErrorKind::InvalidTime)),
+    }
+  }
+
+  /// The beginning of the given date in the time zone.
+  fn midnight(&self, date: Date) -> ParseResult<DateTime> {
+    self.at(date, (0, 0, 0), 0, 0)
+  }
+
+  fn peek(&self) -> Option<&str> {
+    self.word_at(self.ix)
+  }
+
+  fn word_at(&self, ix: usize) -> Option<&str> {
+    self.words.get(ix).map(|w| w.text.as_str())
+  }
+
+  fn weekday_at(&self, ix: usize) -> Option<i32> {
+    name_index(&WEEKDAYS, self.word_at(ix)).map(|i| i as i32)
+  }
+
+  fn period_at(&self, ix: usize) -> Option<Period> {
+    match self.word_at(ix)? {
+      "day" => Some(Period::Day),
+      "week" => Some(Period::Week),
+      "month" => Some(Period::Month),
+      "year" => Some(Period::Year),
+      _ => None,
+    }
+  }
+
+  /// Consume the given word if it is next.
+  fn eat(&mut self, word: &str) -> bool {
+    let matched = self.peek() == Some(word);
+    if matched {
+      self.ix += 1;
+    }
+    matched
+  }
+
+  /// Consume the given word, or fail.
+  fn expect(&mut self, word: &str) -> ParseResult<()> {
+    match self.eat(word) {
+      true => Ok(()),
+      false => self.fail(self.missing()),
+    }
+  }
+
+  /// The index at which the current word begins (or the end of the input).
+  fn start(&self) -> usize {
+    self.words.get(self.ix).map_or(self.src.len(), |w| w.start)
+  }
+
+  /// The error kind for a missing or unexpected word at the current position.
+  fn missing(&self) -> ParseErrorKind {
+    match self.peek() {
+      Some(_) => ParseErrorKind::Unexpected,
+      None => ParseErrorKind::InputTooShort,
+    }
+  }
+
+  fn err_at(&self, index: usize, kind: ParseErrorKind) -> ParseError {
+    ParseError { src: self.src.into(), index: Some(index), kind }
+  }
+
+  fn fail<T>(&self, kind: ParseErrorKind) -> ParseResult<T> {
+    Err(self.err_at(self.start(), kind))
+  }
+}
+
+/// Find a (lowercase) word among the given names, accepting abbreviations of three or more
+/// letters.
+fn name_index(names: &[&str], word: Option<&str>) -> Option<usize> {
+  let word = word.filter(|w| w.len() >= 3)?;
+  names.iter().position(|n| n.len() >= word.len() && n[..word.len()].eq_ignore_ascii_case(word))
+}
+
+/// Parse an ordinal (`first` through `fifth`, or `last`), returning 1–5, or 0 for `last`.
+fn ordinal(word: &str) -> Option<i32> {
+  match word {
+    "first" | "1st" => Some(1),
+    "second" | "2nd" => Some(2),
+    "third" | "3rd" => Some(3),
+    "fourth" | "4th" => Some(4),
+    "fifth" | "5th" => Some(5),
+    "last" => Some(0),
+    _ => None,
+  }
+}
+
+/// Parse a quantity (`a`, `an`, `2`, or `1.5`), returning it as a mantissa and a power of ten to
+/// divide it by.
+///
+/// Quantities of more than [`MAX_DIGITS`] digits saturate, so that they are reported as out of
+/// range rather than as unrecognized input.
+fn quantity(word: &str) -> Option<(i128, i128)> {
+  if word == "a" || word == "an" {
+    return Some((1, 1));
+  }
+  let (int, frac) = word.split_once('.').unwrap_or((word, ""));
+  if int.is_empty()
+    || frac.len() > 9
+    || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+  {
+    return None;
+  }
+  let mantissa = match int.len() + frac.len() {
+    ..=MAX_DIGITS => format!("{int}{frac}").parse().ok()?,
+    _ => i128::MAX,
+  };
+  Some((mantissa, 10i128.pow(frac.len() as u32)))
+}
+
+/// The most digits a quantity may have; larger ones cannot be represented as a shift anyway.
+const MAX_DIGITS: usize = 30;
+
+/// Parse a duration in the `time_interval!` language, such as `1d`, `2h30m`, or `10.5s`,
+/// returning the kind of error if it is out of range.
+fn compact(word: &str) -> Option<Result<Shift, ParseErrorKind>> {
+  let mut shift = Ok(Shift::default());
+  let mut rest = word;
+  let mut units = "dhms";
+  while !rest.is_empty() {
+    let digits = rest.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, symbol) = (&rest[..digits], rest[digits..].chars().next()?);
+    units = &units[units.find(symbol)? + 1..];
+    let (mantissa, scale) = quantity(number)?;
+    let unit = match symbol {
+      'd' if scale == 1 => unit("day")?,
+      'h' if scale == 1 => unit("hour")?,
+      'm' if scale == 1 => unit("minute")?,
+      's' => unit("second")?,
+      _ => return None,
+    };
+    shift = shift.and_then(|s| s.checked_add(&unit.scaled(mantissa, scale)?));
+    rest = &rest[digits + 1..];
+  }
+  Some(shift)
+}
+
+/// The shift for a single unit of time, such as `hour` or `days`.
+fn unit(word: &str) -> Option<Shift> {
+  let (months, days, nanos) = match word.strip_suffix('s').unwrap_or(word) {
+    "second" | "sec" => (0, 0, 1_000_000_000),
+    "minute" | "min" => (0, 0, 60_000_000_000),
+    "hour" | "hr" => (0, 0, 3_600_000_000_000),
+    "day" => (0, 1, 0),
+    "week" => (0, 7, 0),
+    "month" => (1, 0, 0),
+    "year" => (12, 0, 0),
+    _ => return None,
+  };
+  Some(Shift { months, days, nanos })
+}
+
+/// Move a date by the given number of months, clamping the day to the end of the month, or
+/// `None` if the result is out of range.
+fn add_months(date: Date, months: i32) -> Option<Date> {
+  let total = date.year() as i64 * 12 + date.month() as i64 - 1 + months as i64;
+  let (year, month) = (i16::try_from(total.div_euclid(12)).ok()?, total.rem_euclid(12) as u8 + 1);
+  Some(Date::new(year, month, date.day().min(days_in_month(year, month))))
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+  use date::date;
+
+  use super::*;
+  use crate::tz::us;
+
+  /// Thursday, July 4, 2024, at 9:30 AM.
+  const REFERENCE: DateTime = datetime! { 2024-07-04 09:30:00 us::EASTERN };
+
+  fn parse(s: &str) -> ParseResult<RelativeDateTime> {
+    DateTime::parse_relative(s, REFERENCE, us::EASTERN)
+  }
+
+  fn day(date: Date) -> RelativeDateTime {
+    RelativeDateTime::Span {
+      start: date.hms(0, 0, 0).tz(us::EASTERN).unwrap().build(),
+      end: (date + DateInterval::new(1)).hms(0, 0, 0).tz(us::EASTERN).unwrap().build(),
+    }
+  }
+
+  #[test]
+  fn test_instants() -> ParseResult<()> {
+    for (s, expected) in [
+      ("now", datetime! { 2024-07-04 09:30:00 us::EASTERN }),
+      ("Tomorrow at 3pm", datetime! { 2024-07-05 15:00:00 us::EASTERN }),
+      ("tomorrow 3 PM", datetime! { 2024-07-05 15:00:00 us::EASTERN }),
+      ("3 pm tomorrow", datetime! { 2024-07-05 15:00:00 us::EASTERN }),
+      ("3pm tomorrow", datetime! { 2024-07-05 15:00:00 us::EASTERN }),
+      ("noon yesterday", datetime! { 2024-07-03 12:00:00 us::EASTERN }),
+      ("at 17:45", datetime! { 2024-07-04 17:45:00 us::EASTERN }),
+      ("12am", datetime! { 2024-07-04 00:00:00 us::EASTERN }),
+      ("next Friday at 9:15:30 am", datetime! { 2024-07-05 09:15:30 us::EASTERN }),
+      ("in 2 hours", datetime! { 2024-07-04 11:30:00 us::EASTERN }),
+      ("in 1.5 minutes", datetime! { 2024-07-04 09:31:30 us::EASTERN }),
+      ("in an hour and 30 minutes", datetime! { 2024-07-04 11:00:00 us::EASTERN }),
+      ("in 2h 30m", datetime! { 2024-07-04 12:00:00 us::EASTERN }),
+      ("1d12h from now", datetime! { 2024-07-05 21:30:00 us::EASTERN }),
+      ("3 days ago", datetime! { 2024-07-01 09:30:00 us::EASTERN }),
+      ("2 weeks ago", datetime! { 2024-06-20 09:30:00 us::EASTERN }),
+      ("in 1 month", datetime! { 2024-08-04 09:30:00 us::EASTERN }),
+      ("a year ago", datetime! { 2023-07-04 09:30:00 us::EASTERN }),
+      ("in 5 months", datetime! { 2024-12-04 09:30:00 us::EASTERN }),
+      ("end of month", datetime! { 2024-07-31 23:59:59.999_999_999 us::EASTERN }),
+      ("end of the year", datetime! { 2024-12-31 23:59:59.999_999_999 us::EASTERN }),
+      ("start of next week", datetime! { 2024-07-08 00:00:00 us::EASTERN }),
+      ("beginning of last month", datetime! { 2024-06-01 00:00:00 us::EASTERN }),
+    ] {
+      check!(parse(s)? == RelativeDateTime::Instant(expected), "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_days() -> ParseResult<()> {
+    for (s, expected) in [
+      ("today", date! { 2024-07-04 }),
+      ("tomorrow", date! { 2024-07-05 }),
+      ("Thursday", date! { 2024-07-04 }),
+      ("this thu", date! { 2024-07-04 }),
+      ("next Thursday", date! { 2024-07-11 }),
+      ("last Thursday", date! { 2024-06-27 }),
+      ("on Friday", date! { 2024-07-05 }),
+      ("next Friday", date! { 2024-07-05 }),
+      ("last Friday", date! { 2024-06-28 }),
+      ("last Monday of June", date! { 2024-06-24 }),
+      ("first Monday of September 2025", date! { 2025-09-01 }),
+      ("2nd Tuesday in next month", date! { 2024-08-13 }),
+      ("last Wednesday of this month", date! { 2024-07-31 }),
+    ] {
+      check!(parse(s)? == day(expected), "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_spans() -> ParseResult<()> {
+    let midnight = |date: Date| date.hms(0, 0, 0).tz(us::EASTERN).unwrap().build();
+    for (s, start, end) in [
+      ("this week", date! { 2024-07-01 }, date! { 2024-07-08 }),
+      ("next week", date! { 2024-07-08 }, date! { 2024-07-15 }),
+      ("last month", date! { 2024-06-01 }, date! { 2024-07-01 }),
+      ("next year", date! { 2025-01-01 }, date! { 2026-01-01 }),
+    ] {
+      let span = parse(s)?;
+      check!(span == RelativeDateTime::Span { start: midnight(start), end: midnight(end) }, "{s}");
+      check!(span.start() == midnight(start));
+      check!(span.end() == midnight(end));
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_tz() -> ParseResult<()> {
+    // 11:00 PM on July 4 in Los Angeles is already July 5 in New York.
+    let reference = datetime! { 2024-07-04 23:00:00 us::PACIFIC };
+    let dt = DateTime::parse_relative("tomorrow at 8am", reference, us::EASTERN)?;
+    check!(dt == RelativeDateTime::Instant(datetime! { 2024-07-06 08:00:00 us::EASTERN }));
+
+    // Calendar days keep the wall clock time across a DST transition; hours do not.
+    let reference = datetime! { 2024-03-09 12:00:00 us::EASTERN };
+    let dt = DateTime::parse_relative("in 1 day", reference, us::EASTERN)?;
+    check!(dt == RelativeDateTime::Instant(datetime! { 2024-03-10 12:00:00 us::EASTERN }));
+    let dt = DateTime::parse_relative("in 24 hours", reference, us::EASTERN)?;
+    check!(dt == RelativeDateTime::Instant(datetime! { 2024-03-10 13:00:00 us::EASTERN }));
+    Ok(())
+  }
+
+  #[test]
+  fn test_errors() {
+    for (s, index, kind) in [
+      ("", 0, ParseErrorKind::InputTooShort),
+      ("whenever", 0, ParseErrorKind::Unexpected),
+      ("tomorrow at", 11, ParseErrorKind::InputTooShort),
+      ("tomorrow at teatime", 12, ParseErrorKind::Unexpected),
+      ("tomorrow at 13pm", 12, ParseErrorKind::InvalidTime),
+      ("today at 25:00", 9, ParseErrorKind::InvalidTime),
+      ("in 2 fortnights", 5, ParseErrorKind::Unexpected),
+      ("in 1.5 days", 3, ParseErrorKind::Unexpected),
+      ("3 days", 6, ParseErrorKind::InputTooShort),
+      ("3 days hence", 7, ParseErrorKind::Unexpected),
+      ("3 fortnights ago", 0, ParseErrorKind::Unexpected),
+      ("fifth Monday of June", 0, ParseErrorKind::InvalidDate),
+      ("end of time", 7, ParseErrorKind::Unexpected),
+      ("tomorrow or so", 9, ParseErrorKind::InputTooLong),
+      ("in 40000 years", 3, ParseErrorKind::InvalidDate),
+      ("in 100000 years", 3, ParseErrorKind::InvalidDate),
+      ("in 4000000000 days", 3, ParseErrorKind::InvalidDate),
+      ("in 2000000000 days", 3, ParseErrorKind::InvalidDate),
+      ("in 2147483647 months", 3, ParseErrorKind::InvalidDate),
+      ("in 99999999999999999999999999999 hours", 3, ParseErrorKind::InvalidTime),
+      ("99999999999999999999999999999999999h ago", 0, ParseErrorKind::InvalidTime),
+      ("in 1000000000000000 hours", 3, ParseErrorKind::InvalidTime),
+      ("in 1d 99999999999999999999999999999999999999999s", 6, ParseErrorKind::InvalidTime),
+      ("in 3000000000d", 3, ParseErrorKind::InvalidDate),
+    ] {
+      let err = parse(s).unwrap_err();
+      check!(err.index == Some(index), "{s:?}");
+      check!(err.kind == kind, "{s:?}");
+    }
+  }
+
+  #[test]
+  fn test_range() {
+    for (s, index, kind) in [
+      ("in 1 second", 3, ParseErrorKind::InvalidTime),
+      ("tomorrow", 0, ParseErrorKind::InvalidTime),
+      ("next year", 5, ParseErrorKind::InvalidDate),
+    ] {
+      let err = DateTime::parse_relative(s, DateTime::MAX, us::EASTERN).unwrap_err();
+      check!(err.index == Some(index), "{s:?}");
+      check!(err.kind == kind, "{s:?}");
+    }
+  }
+}
@@ -0,0 +1,286 @@
+//! RFC 2822 / RFC 5322 (email) timestamps, such as `Sat, 21 Apr 2012 11:00:00 -0400`.
+
+use crate::DateTime;
+use crate::ParseErrorKind;
+use crate::ParseResult;
+use crate::format::Padding;
+use crate::parse::Input;
+use crate::parse::MONTHS;
+use crate::parse::WEEKDAYS;
+use crate::parse::days_in_month;
+
+impl DateTime {
+  /// Parse an RFC 5322 (formerly RFC 2822) date and time, as found in email headers.
+  ///
+  /// In addition to the current grammar (`Sat, 21 Apr 2012 11:00:00 -0400`), this accepts the
+  /// obsolete forms that RFC 5322 requires readers to understand:
+  ///
+  /// - Two- and three-digit years (`12` is 2012, `97` is 1997, and `112` is 2012).
+  /// - Named time zones (`UT`, `GMT`, and the North American `EST`, `EDT`, `CST`, `CDT`, `MST`,
+  ///   `MDT`, `PST`, and `PDT`).
+  /// - Military (single-letter) and other unrecognized alphabetic time zones.
+  /// - Comments (such as `(EDT)`) and folding whitespace between any two tokens.
+  ///
+  /// Numeric and named time zones produce a date and time with that fixed UTC offset. Following
+  /// RFC 5322, `-0000`, military time zones, and unrecognized time zones convey no information
+  /// about the local time zone; they are treated as UTC, and produce a date and time with no time
+  /// zone. The day of the week, if present, is not checked against the date.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::DateTime;
+  /// use datetime::datetime;
+  ///
+  /// let dt = DateTime::parse_rfc2822("Sat, 21 Apr 2012 11:00:00 -0400 (EDT)").unwrap();
+  /// assert_eq!(dt, datetime! { 2012-04-21 15:00:00 });
+  /// ```
+  pub fn parse_rfc2822(s: impl AsRef<str>) -> ParseResult<Self> {
+    let mut input = Input::new(s.as_ref());
+
+    // Day of the week (optional), and the date.
+    input.cfws()?;
+    if let Some('A'..='Z' | 'a'..='z') = input.peek() {
+      input.name(&WEEKDAYS)?;
+      input.cfws()?;
+      input.expect(',')?;
+      input.cfws()?;
+    }
+    let day_start = input.pos();
+    let day = input.int(2, Padding::Suppress)? as u8;
+    input.fws()?;
+    let month = input.name(&MONTHS)? as u8 + 1;
+    input.fws()?;
+    let year_start = input.pos();
+    let year = match input.digits_ahead() {
+      2 => match input.int(2, Padding::Zero)? as i16 {
+        y @ 0..50 => 2000 + y,
+        y => 1900 + y,
+      },
+      3 => 1900 + input.int(3, Padding::Zero)? as i16,
+      4 => input.int(4, Padding::Zero)? as i16,
+      0 | 1 => input.fail(ParseErrorKind::Unexpected)?,
+      _ => Err(input.err_at(year_start, ParseErrorKind::InvalidDate))?,
+    };
+    if day == 0 || day > days_in_month(year, month) {
+      return Err(input.err_at(day_start, ParseErrorKind::InvalidDate));
+    }
+    input.fws()?;
+
+    // Time of day.
+    let mut hms = [0; 3];
+    for (ix, limit) in [24, 60, 60].into_iter().enumerate() {
+      if ix > 0 {
+        input.cfws()?;
+        if ix == 2 && input.peek() != Some(':') {
+          break;
+        }
+        input.expect(':')?;
+        input.cfws()?;
+      }
+      let start = input.pos();
+      hms[ix] = input.int(2, Padding::Zero)? as u8;
+      if hms[ix] >= limit {
+        return Err(input.err_at(start, ParseErrorKind::InvalidTime));
+      }
+    }
+    input.cfws()?;
+
+    // Time zone.
+    let start = input.pos();
+    let offset = match input.peek() {
+      Some('+' | '-') => {
+        let sign = input.sign()?;
+        let hhmm = input.int(4, Padding::Zero)? as i32;
+        if hhmm % 100 >= 60 {
+          return Err(input.err_at(start, ParseErrorKind::InvalidTime));
+        }
+        match (sign, hhmm) {
+          (-1, 0) => None,
+          _ => Some(sign * (hhmm / 100 * 3_600 + hhmm % 100 * 60)),
+        }
+      },
+      Some('A'..='Z' | 'a'..='z') => {
+        while let Some('A'..='Z' | 'a'..='z') = input.peek() {
+          input.bump();
+        }
+        match input.since(start).to_ascii_uppercase().as_str() {
+          "UT" | "GMT" => Some(0),
+          "EDT" => Some(-4 * 3_600),
+          "EST" | "CDT" => Some(-5 * 3_600),
+          "CST" | "MDT" => Some(-6 * 3_600),
+          "MST" | "PDT" => Some(-7 * 3_600),
+          "PST" => Some(-8 * 3_600),
+          _ => None,
+        }
+      },
+      Some(_) => input.fail(ParseErrorKind::Unexpected)?,
+      None => input.fail(ParseErrorKind::InputTooShort)?,
+    };
+    input.cfws()?;
+    input.assert_consumed()?;
+
+    let builder = DateTime::ymd(year, month, day).hms(hms[0], hms[1], hms[2]);
+    Ok(match offset {
+      Some(offset) => builder.utc_offset(offset).build(),
+      None => builder.build(),
+    })
+  }
+
+  /// Format this date and time according to RFC 5322 (formerly RFC 2822), as used in email
+  /// headers: `Sat, 21 Apr 2012 11:00:00 -0400`.
+  ///
+  /// Fractional seconds are truncated. A date and time with no time zone is written with a
+  /// `+0000` offset.
+  pub fn to_rfc2822(&self) -> String {
+    let offset = self.tz_offset();
+    let minutes = offset.unsigned_abs() / 60;
+    format!(
+      "{} {}{:02}{:02}",
+      self.format("%a, %d %b %Y %H:%M:%S"),
+      if offset < 0 { '-' } else { '+' },
+      minutes / 60,
+      minutes % 60,
+    )
+  }
+}
+
+impl Input<'_> {
+  /// Skip any comments and folding whitespace, returning whether there were any.
+  fn cfws(&mut self) -> ParseResult<bool> {
+    let start = self.pos();
+    loop {
+      match self.peek() {
+        Some(' ' | '\t' | '\r' | '\n') => {
+          self.bump();
+        },
+        Some('(') => self.comment()?,
+        _ => break,
+      }
+    }
+    Ok(self.pos() > start)
+  }
+
+  /// Skip comments and folding whitespace, requiring that there be some.
+  fn fws(&mut self) -> ParseResult<()> {
+    match self.cfws()? {
+      true => Ok(()),
+      false if self.peek().is_none() => self.fail(ParseErrorKind::InputTooShort),
+      false => self.fail(ParseErrorKind::Unexpected),
+    }
+  }
+
+  /// Skip a (possibly nested) parenthesized comment.
+  fn comment(&mut self) -> ParseResult<()> {
+    let start = self.pos();
+    let mut depth = 0;
+    loop {
+      match self.bump() {
+        Some('(') => depth += 1,
+        Some(')') if depth == 1 => return Ok(()),
+        Some(')') => depth -= 1,
+        Some('\\') if self.bump().is_some() => {},
+        Some(_) => {},
+        None => return Err(self.err_at(start, ParseErrorKind::InputTooShort)),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+
+  #[test]
+  fn test_parse_rfc2822() -> ParseResult<()> {
+    for (s, expected) in [
+      ("Sat, 21 Apr 2012 11:00:00 -0400", datetime! { 2012-04-21 15:00:00 }),
+      ("Sat, 21 Apr 2012 11:00:00 -0400 (EDT)", datetime! { 2012-04-21 15:00:00 }),
+      ("21 Apr 2012 11:00 -0400", datetime! { 2012-04-21 15:00:00 }),
+      ("Fri, 21 Nov 1997 09:55:06 -0600", datetime! { 1997-11-21 15:55:06 }),
+      ("Tue, 1 Jul 2003 10:52:37 +0200", datetime! { 2003-07-01 08:52:37 }),
+      ("Thu, 13 Feb 1969 23:32:54 -0330", datetime! { 1969-02-14 03:02:54 }),
+      // Obsolete forms.
+      ("Fri, 21 Nov 97 09:55:06 GMT", datetime! { 1997-11-21 09:55:06 }),
+      ("Sat, 21 Apr 12 11:00:00 EDT", datetime! { 2012-04-21 15:00:00 }),
+      ("Sat, 21 Apr 112 11:00:00 PST", datetime! { 2012-04-21 19:00:00 }),
+      ("Saturday, 21 April 2012 11:00:00 UT", datetime! { 2012-04-21 11:00:00 }),
+      ("Sat, 21 Apr 2012 11:00:00 Z", datetime! { 2012-04-21 11:00:00 }),
+      ("Sat, 21 Apr 2012 11:00:00 Q", datetime! { 2012-04-21 11:00:00 }),
+      ("Sat, 21 Apr 2012 11:00:00 WAT", datetime! { 2012-04-21 11:00:00 }),
+      ("Sat, 21 Apr 2012 11:00:00 -0000", datetime! { 2012-04-21 11:00:00 }),
+      ("sat, 21 apr 2012 11:00:00 est", datetime! { 2012-04-21 16:00:00 }),
+      (
+        "Thu,\r\n      13\r\n        Feb\r\n          1969\r\n      23:32\r\n               \
+         -0330 (Newfoundland Time)",
+        datetime! { 1969-02-14 03:02:00 },
+      ),
+      (
+        "(Comment) Fri (Weekday), 21 Nov 1997 09 (hour) : 55 : 06 (second) -0600 ((nested) \
+         com\\)ment)",
+        datetime! { 1997-11-21 15:55:06 },
+      ),
+    ] {
+      check!(DateTime::parse_rfc2822(s)? == expected, "{s}");
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_parse_rfc2822_tz() -> ParseResult<()> {
+    use crate::tz::TimeZone;
+    for (s, tz) in [
+      ("Sat, 21 Apr 2012 11:00:00 -0400", TimeZone::FixedOffset(-14_400)),
+      ("Sat, 21 Apr 2012 11:00:00 EDT", TimeZone::FixedOffset(-14_400)),
+      ("Sat, 21 Apr 2012 11:00:00 +0000", TimeZone::FixedOffset(0)),
+      ("Sat, 21 Apr 2012 11:00:00 GMT", TimeZone::FixedOffset(0)),
+      ("Sat, 21 Apr 2012 11:00:00 -0000", TimeZone::Unspecified),
+      ("Sat, 21 Apr 2012 11:00:00 J", TimeZone::Unspecified),
+    ] {
+      let dt = DateTime::parse_rfc2822(s)?;
+      check!(dt.tz == tz, "{s}");
+      check!(dt.hour() == 11, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_rfc2822_errors() {
+    for (s, index, kind) in [
+      ("Sat 21 Apr 2012 11:00:00 -0400", 4, ParseErrorKind::Unexpected),
+      ("Sat, 31 Apr 2012 11:00:00 -0400", 5, ParseErrorKind::InvalidDate),
+      ("Sat, 21 Foo 2012 11:00:00 -0400", 8, ParseErrorKind::Unexpected),
+      ("Sat, 21 Apr 2 11:00:00 -0400", 12, ParseErrorKind::Unexpected),
+      ("Sat, 21 Apr 20120 11:00:00 -0400", 12, ParseErrorKind::InvalidDate),
+      ("Sat, 21 Apr 2012 24:00:00 -0400", 17, ParseErrorKind::InvalidTime),
+      ("Sat, 21 Apr 2012 11:00:00 -0460", 26, ParseErrorKind::InvalidTime),
+      ("Sat, 21 Apr 2012 11:00:00", 25, ParseErrorKind::InputTooShort),
+      ("Sat, 21 Apr 2012 11:00:00 -0400 (EDT", 32, ParseErrorKind::InputTooShort),
+      ("Sat, 21 Apr 2012 11:00:00 -0400 x", 32, ParseErrorKind::InputTooLong),
+    ] {
+      let err = DateTime::parse_rfc2822(s).unwrap_err();
+      check!(err.index == Some(index), "{s}");
+      check!(err.kind == kind, "{s}");
+    }
+  }
+
+  #[test]
+  fn test_to_rfc2822() {
+    check!(datetime! { 2012-04-21 11:00:00 }.to_rfc2822() == "Sat, 21 Apr 2012 11:00:00 +0000");
+    check!(datetime! { 2003-07-01 10:52:37.5 }.to_rfc2822() == "Tue, 01 Jul 2003 10:52:37 +0000");
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_to_rfc2822_tz() -> ParseResult<()> {
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    check!(dt.to_rfc2822() == "Sat, 21 Apr 2012 11:00:00 -0400");
+    let dt = datetime! { 2012-04-21 11:00:00 america::ST_JOHNS };
+    check!(dt.to_rfc2822() == "Sat, 21 Apr 2012 11:00:00 -0230");
+    check!(DateTime::parse_rfc2822(dt.to_rfc2822())?.hour() == 11);
+    Ok(())
+  }
+}
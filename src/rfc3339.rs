@@ -0,0 +1,844 @@
+//! Fast paths for reading and writing RFC 3339 (and RFC 3339-like) timestamps.
+//!
+//! The general-purpose [`FormattedDateTime`](crate::FormattedDateTime) interprets its format
+//! string on every call. The timestamps written here (by [`DateTime::write_rfc3339`], and by the
+//! `Debug` and `Serialize` implementations) have fixed layouts, so they are instead written
+//! directly into a stack buffer using a two-digit lookup table.
+//!
+//! Similarly, `FromStr` reads timestamps with a hand-written, single-pass parser rather than by
+//! trying a series of format strings.
+
+use std::io;
+use std::str;
+
+use crate::DateTime;
+use crate::ParseErrorKind;
+use crate::ParseResult;
+use crate::Precision;
+use crate::format::Padding;
+use crate::parse::Input;
+use crate::parse::days_in_month;
+
+/// The longest timestamp any layout can produce.
+///
+/// This is `-32768-MM-DDTHH:MM:SS.fffffffff+HH:MM` (37 bytes), rounded up.
+pub(crate) const MAX_LEN: usize = 40;
+
+/// The layout written by [`DateTime::write_rfc3339`].
+pub(crate) const RFC3339: Layout =
+  Layout { sep: b'T', extended: true, fraction: Fraction::Precision, offset: Offset::Rfc3339 };
+
+/// The layout written by the `Debug` implementation.
+pub(crate) const DEBUG: Layout =
+  Layout { sep: b' ', extended: true, fraction: Fraction::Precision, offset: Offset::Omit };
+
+/// The layout written by the `Serialize` implementation.
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+pub(crate) const SERDE: Layout =
+  Layout { sep: b'T', extended: true, fraction: Fraction::Micros, offset: Offset::Compact };
+
+/// The layout written to SQLite, matching the text SQLite's own date and time functions produce.
+#[cfg_attr(not(feature = "diesel-sqlite"), allow(dead_code))]
+pub(crate) const SQLITE: Layout =
+  Layout { sep: b' ', extended: true, fraction: Fraction::Precision, offset: Offset::Omit };
+
+/// The shape of a timestamp.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Layout {
+  /// The byte separating the date from the time.
+  pub(crate) sep: u8,
+  /// Whether to separate date components with `-` and time components with `:`.
+  pub(crate) extended: bool,
+  /// How many fractional digits to write.
+  pub(crate) fraction: Fraction,
+  /// How to write the UTC offset.
+  pub(crate) offset: Offset,
+}
+
+/// How many fractional second digits to write.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Fraction {
+  /// The fewest of 0, 3, 6, or 9 digits that represent the value exactly.
+  Precision,
+  /// The fewest of 0, 6, or 9 digits that represent the value exactly.
+  Micros,
+  /// Exactly this many digits (`0..=9`), truncating any remainder.
+  Exact(u8),
+}
+
+/// How to write the UTC offset.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Offset {
+  /// Do not write an offset.
+  Omit,
+  /// Write `±HHMM`, or nothing if no time zone is set.
+  Compact,
+  /// Write `±HH:MM`, or `Z` if no time zone is set.
+  Rfc3339,
+  /// Write `±HHMM`, or `Z` if no time zone is set.
+  Basic,
+}
+
+impl DateTime {
+  /// Write this date and time to the given writer as an RFC 3339 timestamp.
+  ///
+  /// Fractional seconds are written with the fewest digits (0, 3, 6, or 9) that represent the
+  /// value exactly. A date and time with no time zone is written with a `Z` suffix; otherwise the
+  /// UTC offset is written as `±HH:MM`.
+  ///
+  /// This method does not allocate.
+  pub fn write_rfc3339(&self, w: &mut impl io::Write) -> io::Result<()> {
+    let mut buf = [0; MAX_LEN];
+    let len = self.write_layout(RFC3339, &mut buf);
+    w.write_all(&buf[..len])
+  }
+
+  /// Write this date and time into the given buffer as an RFC 3339 timestamp (in the same form as
+  /// [`DateTime::write_rfc3339`]), and return the number of bytes written.
+  ///
+  /// ## Errors
+  ///
+  /// Returns an error of kind [`io::ErrorKind::WriteZero`] if the buffer is too small, in which
+  /// case the buffer is left unmodified. A buffer of 40 bytes is always sufficient.
+  pub fn format_into(&self, buf: &mut [u8]) -> io::Result<usize> {
+    let mut out = [0; MAX_LEN];
+    let len = self.write_layout(RFC3339, &mut out);
+    let dest = buf.get_mut(..len).ok_or(io::ErrorKind::WriteZero)?;
+    dest.copy_from_slice(&out[..len]);
+    Ok(len)
+  }
+
+  /// Format this date and time as an RFC 3339 timestamp with exactly the given precision.
+  ///
+  /// Any precision finer than the one requested is truncated. A date and time with no time zone is
+  /// written with a `Z` suffix; otherwise the UTC offset is written as `±HH:MM`.
+  ///
+  /// ## Examples
+  ///
+  /// ```
+  /// use datetime::Precision;
+  /// use datetime::datetime;
+  ///
+  /// let dt = datetime! { 2024-07-04 15:30:45.123456 };
+  /// assert_eq!(dt.to_rfc3339(Precision::Second), "2024-07-04T15:30:45Z");
+  /// assert_eq!(dt.to_rfc3339(Precision::Millisecond), "2024-07-04T15:30:45.123Z");
+  /// assert_eq!(dt.to_rfc3339(Precision::Nanosecond), "2024-07-04T15:30:45.123456000Z");
+  /// ```
+  pub fn to_rfc3339(&self, precision: Precision) -> String {
+    let layout = Layout { fraction: Fraction::Exact(precision.digits()), ..RFC3339 };
+    self.layout_str(layout, &mut [0; MAX_LEN]).to_owned()
+  }
+
+  /// Parse a timestamp that conforms strictly to the RFC 3339 grammar, such as
+  /// `2012-04-21T11:00:00.5-04:00`.
+  ///
+  /// Unlike `FromStr`, this requires a `T` (or `t`) between the date and time, and requires a UTC
+  /// offset of either `Z` or `±HH:MM`. Fractional seconds may have any number of digits; digits
+  /// beyond nanosecond precision are truncated.
+  ///
+  /// A `Z` or `-00:00` offset (the latter of which RFC 3339 uses to indicate an unknown local
+  /// offset) yields a date and time with no time zone; any other offset yields a date and time in
+  /// that fixed offset.
+  pub fn parse_rfc3339(s: impl AsRef<str>) -> ParseResult<Self> {
+    let mut input = Input::new(s.as_ref());
+    let (year, month, day) = input.ymd()?;
+    match input.peek() {
+      Some('T' | 't') => {
+        input.bump();
+      },
+      Some(_) => input.fail(ParseErrorKind::Unexpected)?,
+      None => input.fail(ParseErrorKind::InputTooShort)?,
+    }
+    let (hour, minute, second) = input.hms()?;
+    let nanos = input.fraction(usize::MAX)?;
+    let offset = match input.peek() {
+      Some('Z' | 'z') => {
+        input.bump();
+        None
+      },
+      Some('+' | '-') => {
+        let start = input.pos();
+        let sign = input.sign()?;
+        let hours = input.int(2, Padding::Zero)? as i32;
+        input.expect(':')?;
+        let minutes = input.int(2, Padding::Zero)? as i32;
+        if hours >= 24 || minutes >= 60 {
+          return Err(input.err_at(start, ParseErrorKind::InvalidTime));
+        }
+        match (sign, hours, minutes) {
+          (-1, 0, 0) => None,
+          _ => Some(sign * (hours * 3_600 + minutes * 60)),
+        }
+      },
+      Some(_) => input.fail(ParseErrorKind::Unexpected)?,
+      None => input.fail(ParseErrorKind::InputTooShort)?,
+    };
+    input.assert_consumed()?;
+
+    let builder = DateTime::ymd(year, month, day).hms(hour, minute, second).nanos(nanos);
+    Ok(match offset {
+      Some(offset) => builder.utc_offset(offset).build(),
+      None => builder.build(),
+    })
+  }
+
+  /// Write this date and time into a stack buffer in the given layout, and return it as a string.
+  pub(crate) fn layout_str<'b>(&self, layout: Layout, buf: &'b mut [u8; MAX_LEN]) -> &'b str {
+    let len = self.write_layout(layout, buf);
+    str::from_utf8(&buf[..len]).expect("timestamps are always ASCII")
+  }
+
+  /// Write this date and time into a stack buffer in the given layout, and return its length.
+  pub(crate) fn write_layout(&self, layout: Layout, buf: &mut [u8; MAX_LEN]) -> usize {
+    let offset = self.layout_offset();
+    let local = self.seconds + offset.unwrap_or_default() as i64;
+    let (year, month, day) = civil_from_days(local.div_euclid(86_400));
+    let secs = local.rem_euclid(86_400) as u32;
+
+    // Write the date and time.
+    let mut pos = match year {
+      0..=9_999 => {
+        put2(buf, 0, (year / 100) as u32);
+        put2(buf, 2, (year % 100) as u32);
+        4
+      },
+      _ => put_year(buf, year),
+    };
+    let (date_sep, time_sep) =
+      if layout.extended { (Some(b'-'), Some(b':')) } else { (None, None) };
+    for (value, sep) in [
+      (month, date_sep),
+      (day, date_sep),
+      (secs / 3_600, Some(layout.sep)),
+      (secs % 3_600 / 60, time_sep),
+      (secs % 60, time_sep),
+    ] {
+      if let Some(sep) = sep {
+        buf[pos] = sep;
+        pos += 1;
+      }
+      put2(buf, pos, value);
+      pos += 2;
+    }
+
+    // Write the fractional seconds.
+    let digits = match layout.fraction {
+      Fraction::Exact(digits) => digits.min(9) as u32,
+      _ if self.nanos == 0 => 0,
+      Fraction::Precision if self.nanos % 1_000_000 == 0 => 3,
+      _ if self.nanos % 1_000 == 0 => 6,
+      _ => 9,
+    };
+    if digits > 0 {
+      buf[pos] = b'.';
+      let mut value = self.nanos / 10u32.pow(9 - digits);
+      for ix in (pos + 1..=pos + digits as usize).rev() {
+        buf[ix] = b'0' + (value % 10) as u8;
+        value /= 10;
+      }
+      pos += digits as usize + 1;
+    }
+
+    // Write the offset.
+    match (layout.offset, offset) {
+      (Offset::Omit, _) | (Offset::Compact, None) => {},
+      (Offset::Rfc3339 | Offset::Basic, None) => {
+        buf[pos] = b'Z';
+        pos += 1;
+      },
+      (Offset::Compact | Offset::Rfc3339 | Offset::Basic, Some(offset)) => {
+        buf[pos] = if offset < 0 { b'-' } else { b'+' };
+        let minutes = offset.unsigned_abs() / 60;
+        put2(buf, pos + 1, minutes / 60);
+        pos += 3;
+        if let Offset::Rfc3339 = layout.offset {
+          buf[pos] = b':';
+          pos += 1;
+        }
+        put2(buf, pos, minutes % 60);
+        pos += 2;
+      },
+    }
+    pos
+  }
+
+  /// The UTC offset to write, in seconds, or `None` if there is no time zone.
+  pub(crate) const fn layout_offset(&self) -> Option<i32> {
+    #[cfg(feature = "tz")]
+    if !matches!(self.tz, crate::tz::TimeZone::Unspecified) {
+      return Some(self.tz_offset() as i32);
+    }
+    None
+  }
+}
+
+/// Parse an ISO 8601 / RFC 3339 timestamp in a single pass.
+///
+/// This is the parser behind `FromStr`, and is deliberately lenient. It accepts:
+///
+/// - A `YYYY-MM-DD` date and `HH:MM:SS` time, separated by `T`, `t`, or a space.
+/// - Optionally, a fractional second of one to nine digits.
+/// - Optionally, `Z` (or `z`), or a UTC offset of the form `±HH:MM`, `±HHMM`, or `±HH`.
+/// - Optionally, RFC 9557 annotations, such as `[America/New_York]` or `[u-ca=iso8601]`.
+///
+/// A trailing `Z` is treated the same as no offset at all.
+///
+/// A time zone annotation is either an IANA name or a UTC offset, and may be marked critical with
+/// `!`. With a UTC offset (or `Z`), the annotation must agree with it at that instant; without
+/// one, the date and time is the wall clock time in that time zone. Other annotations are ignored,
+/// unless they are marked critical. Without the `tz` feature, IANA names are checked for syntax
+/// but otherwise ignored.
+pub(crate) fn parse_lenient(src: &str) -> ParseResult<DateTime> {
+  let mut input = Input::new(src);
+  let (year, month, day) = input.ymd()?;
+  match input.peek() {
+    Some('T' | 't' | ' ') => {
+      input.bump();
+    },
+    Some(_) => input.fail(ParseErrorKind::Unexpected)?,
+    None => input.fail(ParseErrorKind::InputTooShort)?,
+  }
+  let (hour, minute, second) = input.hms()?;
+  let nanos = input.fraction(9)?;
+  let offset_start = input.pos();
+  let (offset, utc) = match input.peek() {
+    Some('Z' | 'z') => {
+      input.bump();
+      (None, true)
+    },
+    Some('+' | '-') => (Some(input.lenient_offset()?), false),
+    _ => (None, false),
+  };
+  let zone = input.annotations()?;
+  input.assert_consumed()?;
+
+  let builder = || DateTime::ymd(year, month, day).hms(hour, minute, second).nanos(nanos);
+  let dt = match offset {
+    Some(offset) => builder().utc_offset(offset).build(),
+    None => builder().build(),
+  };
+  #[cfg_attr(not(feature = "tz"), allow(unused_variables))]
+  let Some((zone_start, zone)) = zone else {
+    return Ok(dt);
+  };
+  match zone {
+    Zone::Offset(zone) if offset.is_some_and(|offset| offset != zone) =>
+      Err(input.err_at(offset_start, ParseErrorKind::InconsistentOffset)),
+    Zone::Offset(zone) if offset.is_none() && !utc => Ok(builder().utc_offset(zone).build()),
+    #[cfg(feature = "tz")]
+    Zone::Offset(zone) => Ok(DateTime { tz: crate::tz::TimeZone::FixedOffset(zone), ..dt }),
+    #[cfg(feature = "tz")]
+    Zone::Name(name) => {
+      let (name, tz) = crate::tz::by_name(name)
+        .ok_or_else(|| input.err_at(zone_start, ParseErrorKind::UnknownTimeZone))?;
+      let dt = match offset.is_some() || utc {
+        true => dt,
+        false => builder()
+          .tz(tz)
+          .map_err(|_| input.err_at(zone_start, ParseErrorKind::InvalidTime))?
+          .build(),
+      };
+      let dt = DateTime { tz: crate::tz::TimeZone::Named(name, tz), ..dt };
+      match offset.is_some_and(|offset| offset as i64 != dt.tz_offset()) {
+        true => Err(input.err_at(offset_start, ParseErrorKind::InconsistentOffset)),
+        false => Ok(dt),
+      }
+    },
+    #[cfg(not(feature = "tz"))]
+    _ => Ok(dt),
+  }
+}
+
+/// A time zone annotation (RFC 9557 § 4).
+enum Zone<'a> {
+  /// An IANA time zone name, such as `America/New_York`.
+  #[cfg_attr(not(feature = "tz"), allow(dead_code))]
+  Name(&'a str),
+  /// A UTC offset, in seconds.
+  Offset(i32),
+}
+
+impl<'a> Input<'a> {
+  /// Parse RFC 9557 suffix annotations, such as `[America/New_York][u-ca=iso8601]`, and return the
+  /// time zone annotation (and where it starts), if there is one.
+  ///
+  /// The time zone annotation must come first. Other annotations are ignored, unless they are
+  /// marked critical with `!`, in which case they are an error.
+  fn annotations(&mut self) -> ParseResult<Option<(usize, Zone<'a>)>> {
+    let mut zone = None;
+    let mut first = true;
+    while self.eat('[') {
+      let critical = self.eat('!');
+      let start = self.pos();
+      while !matches!(self.peek(), Some(']') | None) {
+        self.bump();
+      }
+      let tag = self.since(start);
+      self.expect(']')?;
+      match tag.split_once('=') {
+        Some((key, value)) if !critical && annotation_key(key) && annotation_value(value) => {},
+        None if first && tag.starts_with(['+', '-']) => {
+          let mut offset = Input::new(tag);
+          match offset.lenient_offset().and_then(|o| offset.assert_consumed().map(|_| o)) {
+            Ok(offset) => zone = Some((start, Zone::Offset(offset))),
+            Err(_) => return Err(self.err_at(start, ParseErrorKind::Unexpected)),
+          }
+        },
+        None if first && zone_name(tag) => zone = Some((start, Zone::Name(tag))),
+        _ => return Err(self.err_at(start, ParseErrorKind::Unexpected)),
+      }
+      first = false;
+    }
+    Ok(zone)
+  }
+}
+
+/// Whether the string is a syntactically valid IANA time zone name, such as `America/New_York`.
+fn zone_name(s: &str) -> bool {
+  s.split('/').all(|part| {
+    part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '.' || c == '_')
+      && part.chars().all(|c| c.is_ascii_alphanumeric() || "._-+".contains(c))
+  })
+}
+
+/// Whether the string is a valid annotation key, such as `u-ca`.
+fn annotation_key(s: &str) -> bool {
+  s.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+    && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+/// Whether the string is a valid annotation value, such as `iso8601` or `islamic-civil`.
+fn annotation_value(s: &str) -> bool {
+  s.split('-').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Components shared by the RFC 3339 and ISO 8601 parsers.
+impl Input<'_> {
+  /// Parse and validate a `YYYY-MM-DD` date.
+  pub(crate) fn ymd(&mut self) -> ParseResult<(i16, u8, u8)> {
+    let year = self.int(4, Padding::Zero)? as i16;
+    self.expect('-')?;
+    let start = self.pos();
+    let month = self.int(2, Padding::Zero)? as u8;
+    if !(1..=12).contains(&month) {
+      return Err(self.err_at(start, ParseErrorKind::InvalidDate));
+    }
+    self.expect('-')?;
+    let start = self.pos();
+    let day = self.int(2, Padding::Zero)? as u8;
+    if day == 0 || day > days_in_month(year, month) {
+      return Err(self.err_at(start, ParseErrorKind::InvalidDate));
+    }
+    Ok((year, month, day))
+  }
+
+  /// Parse and validate an `HH:MM:SS` time.
+  pub(crate) fn hms(&mut self) -> ParseResult<(u8, u8, u8)> {
+    let mut hms = [0; 3];
+    for (ix, limit) in [24, 60, 60].into_iter().enumerate() {
+      if ix > 0 {
+        self.expect(':')?;
+      }
+      let start = self.pos();
+      hms[ix] = self.int(2, Padding::Zero)? as u8;
+      if hms[ix] >= limit {
+        return Err(self.err_at(start, ParseErrorKind::InvalidTime));
+      }
+    }
+    Ok((hms[0], hms[1], hms[2]))
+  }
+
+  /// Parse an optional `.` followed by one or more digits, returning the value in nanoseconds.
+  ///
+  /// More than `max_digits` digits is an error; otherwise, digits beyond nanosecond precision are
+  /// truncated.
+  pub(crate) fn fraction(&mut self, max_digits: usize) -> ParseResult<u32> {
+    if !self.eat('.') {
+      return Ok(0);
+    }
+    let mut nanos = 0;
+    let mut digits = 0;
+    while let Some(c @ '0'..='9') = self.peek() {
+      if digits == max_digits {
+        return self.fail(ParseErrorKind::Unexpected);
+      }
+      if digits < 9 {
+        nanos = nanos * 10 + (c as u8 - b'0') as u32;
+      }
+      digits += 1;
+      self.bump();
+    }
+    match digits {
+      0 if self.peek().is_none() => self.fail(ParseErrorKind::InputTooShort),
+      0 => self.fail(ParseErrorKind::Unexpected),
+      _ => Ok(nanos * 10u32.pow(9 - digits.min(9) as u32)),
+    }
+  }
+
+  /// Parse a UTC offset of the form `±HH:MM`, `±HHMM`, or `±HH`, returning it in seconds.
+  fn lenient_offset(&mut self) -> ParseResult<i32> {
+    let start = self.pos();
+    let sign = self.sign()?;
+    let hours = self.int(2, Padding::Zero)? as i32;
+    let minutes = match self.peek() {
+      Some(':') => {
+        self.bump();
+        self.int(2, Padding::Zero)? as i32
+      },
+      Some('0'..='9') => self.int(2, Padding::Zero)? as i32,
+      _ => 0,
+    };
+    if hours >= 24 || minutes >= 60 {
+      return Err(self.err_at(start, ParseErrorKind::InvalidTime));
+    }
+    Ok(sign * (hours * 3_600 + minutes * 60))
+  }
+}
+
+impl Precision {
+  /// The number of fractional second digits this precision requires.
+  pub(crate) const fn digits(self) -> u8 {
+    match self {
+      Self::Second => 0,
+      Self::Millisecond => 3,
+      Self::Microsecond => 6,
+      Self::Nanosecond => 9,
+    }
+  }
+}
+
+/// Convert a number of days since the Unix epoch to a year, month, and day.
+///
+/// The algorithm is taken from here:
+/// https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+const fn civil_from_days(days: i64) -> (i32, u32, u32) {
+  let shifted = days + 719_468; // Days from March 1, 0 A.D.
+  let era = shifted.div_euclid(146_097);
+  let doe = shifted.rem_euclid(146_097); // day of era: [0, 146_097)
+  let year_of_era = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+  let day_of_year = doe - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let mp = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+  (year as i32, month as u32, day as u32)
+}
+
+/// Write a two-digit number (`0..100`) at the given position.
+#[inline(always)]
+fn put2(buf: &mut [u8], pos: usize, value: u32) {
+  let ix = value as usize * 2;
+  buf[pos..pos + 2].copy_from_slice(&DIGITS[ix..ix + 2]);
+}
+
+/// Write a year outside `0..=9999` as a sign (if negative) followed by at least four digits, and
+/// return the number of bytes written.
+#[cold]
+fn put_year(buf: &mut [u8], year: i32) -> usize {
+  let mut pos = 0;
+  if year < 0 {
+    buf[0] = b'-';
+    pos = 1;
+  }
+  let mut value = year.unsigned_abs();
+  let width = if value >= 10_000 { 5 } else { 4 };
+  for ix in (pos..pos + width).rev() {
+    buf[ix] = b'0' + (value % 10) as u8;
+    value /= 10;
+  }
+  pos + width
+}
+
+/// Every two-digit number, in order.
+const DIGITS: &[u8; 200] = b"\
+  0001020304050607080910111213141516171819\
+  2021222324252627282930313233343536373839\
+  4041424344454647484950515253545556575859\
+  6061626364656667686970717273747576777879\
+  8081828384858687888990919293949596979899";
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+  use crate::Precision;
+  use crate::interval::TimeInterval;
+
+  #[test]
+  fn test_write_rfc3339() -> io::Result<()> {
+    for (dt, expected) in [
+      (datetime! { 2012-04-21 11:00:00 }, "2012-04-21T11:00:00Z"),
+      (datetime! { 2024-07-04 15:30:45.5 }, "2024-07-04T15:30:45.500Z"),
+      (datetime! { 2024-07-04 15:30:45.123456 }, "2024-07-04T15:30:45.123456Z"),
+      (datetime! { 2024-07-04 15:30:45.000000001 }, "2024-07-04T15:30:45.000000001Z"),
+      (datetime! { 1969-12-31 23:59:59.25 }, "1969-12-31T23:59:59.250Z"),
+      (datetime! { 0001-01-01 00:00:00 }, "0001-01-01T00:00:00Z"),
+    ] {
+      let mut out = Vec::new();
+      dt.write_rfc3339(&mut out)?;
+      check!(out == expected.as_bytes());
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_write_rfc3339_tz() -> io::Result<()> {
+    for (dt, expected) in [
+      (datetime! { 2012-04-21 11:00:00 us::EASTERN }, "2012-04-21T11:00:00-04:00"),
+      (datetime! { 2012-04-21 11:00:00 europe::LONDON }, "2012-04-21T11:00:00+01:00"),
+      (datetime! { 2012-01-21 11:00:00 europe::LONDON }, "2012-01-21T11:00:00+00:00"),
+      (datetime! { 2012-04-21 11:00:00 asia::KOLKATA }, "2012-04-21T11:00:00+05:30"),
+      (datetime! { 2012-04-21 11:00:00 america::ST_JOHNS }, "2012-04-21T11:00:00-02:30"),
+    ] {
+      let mut out = Vec::new();
+      dt.write_rfc3339(&mut out)?;
+      check!(out == expected.as_bytes());
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_format_into() -> io::Result<()> {
+    let dt = datetime! { 2024-07-04 15:30:45.123456789 };
+    let mut buf = [0; MAX_LEN];
+    let len = dt.format_into(&mut buf)?;
+    check!(&buf[..len] == b"2024-07-04T15:30:45.123456789Z");
+
+    let mut buf = [0; 20];
+    let err = dt.format_into(&mut buf).unwrap_err();
+    check!(err.kind() == io::ErrorKind::WriteZero);
+    check!(buf == [0; 20]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_extreme_years() {
+    let mut buf = [0; MAX_LEN];
+    let dt = DateTime::ymd(-1, 1, 1).build();
+    check!(dt.layout_str(DEBUG, &mut buf) == "-0001-01-01 00:00:00");
+    let dt = DateTime::ymd(i16::MAX, 12, 31).hms(23, 59, 59).nanos(999_999_999).build();
+    check!(dt.layout_str(RFC3339, &mut buf) == "32767-12-31T23:59:59.999999999Z");
+  }
+
+  #[test]
+  fn test_parse_lenient() -> ParseResult<()> {
+    let base = datetime! { 2012-04-21 11:00:00 };
+    for (s, nanos) in [
+      // Everything accepted by the multi-format parser that this replaced.
+      ("2012-04-21T11:00:00", 0),
+      ("2012-04-21 11:00:00", 0),
+      ("2012-04-21T11:00:00.123456", 123_456_000),
+      ("2012-04-21 11:00:00.123456", 123_456_000),
+      ("2012-04-21T11:00:00.123456789", 123_456_789),
+      ("2012-04-21 11:00:00.123456789", 123_456_789),
+      ("2012-04-21 11:00:00Z", 0),
+      ("2012-04-21T11:00:00Z", 0),
+      // New forms.
+      ("2012-04-21t11:00:00z", 0),
+      ("2012-04-21T11:00:00.5", 500_000_000),
+      ("2012-04-21T11:00:00.1234Z", 123_400_000),
+    ] {
+      let dt = parse_lenient(s)?;
+      check!(dt.as_seconds() == base.as_seconds(), "{s}");
+      check!(dt.nanosecond() == nanos, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_lenient_offset() -> ParseResult<()> {
+    let expected = datetime! { 2012-04-21 15:00:00 };
+    for s in [
+      // Everything accepted by the multi-format parser that this replaced.
+      "2012-04-21T11:00:00-0400",
+      "2012-04-21 11:00:00-0400",
+      "2012-04-21T11:00:00.000000-0400",
+      "2012-04-21 11:00:00.000000-0400",
+      "2012-04-21T11:00:00.000000000-0400",
+      "2012-04-21 11:00:00.000000000-0400",
+      // New forms.
+      "2012-04-21T11:00:00-04:00",
+      "2012-04-21T11:00:00-04",
+      "2012-04-21T10:30:00-04:30",
+      "2012-04-21T11:00:00.0-04:00",
+    ] {
+      check!(parse_lenient(s)? == expected, "{s}");
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_parse_lenient_tz() -> ParseResult<()> {
+    let dt = parse_lenient("2012-04-21T11:00:00-04:00")?;
+    check!(dt.hour() == 11);
+    check!(dt.tz == crate::tz::TimeZone::FixedOffset(-14_400));
+    let dt = parse_lenient("2012-04-21T11:00:00Z")?;
+    check!(dt.tz == crate::tz::TimeZone::Unspecified);
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_parse_lenient_annotations() -> ParseResult<()> {
+    let expected = datetime! { 2024-07-04 15:30:45 us::EASTERN };
+    for s in [
+      "2024-07-04T15:30:45-04:00[America/New_York]",
+      "2024-07-04T15:30:45-04:00[!America/New_York]",
+      "2024-07-04T15:30:45-0400[america/new_york][u-ca=iso8601]",
+      "2024-07-04T19:30:45Z[America/New_York]",
+      "2024-07-04T15:30:45[America/New_York]",
+    ] {
+      let dt = parse_lenient(s)?;
+      check!(dt == expected, "{s}");
+      check!(
+        dt.tz == crate::tz::TimeZone::Named("America/New_York", crate::tz::us::EASTERN),
+        "{s}"
+      );
+    }
+
+    let dt = parse_lenient("2024-07-04T15:30:45[-04:00]")?;
+    check!(dt == expected);
+    check!(dt.tz == crate::tz::TimeZone::FixedOffset(-14_400));
+    check!(parse_lenient("2024-07-04T15:30:45-04:00[-04:00]")? == expected);
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_parse_lenient_annotation_errors() {
+    for (s, index, kind) in [
+      ("2024-07-04T15:30:45-05:00[America/New_York]", 19, ParseErrorKind::InconsistentOffset),
+      ("2024-07-04T15:30:45-05:00[-04:00]", 19, ParseErrorKind::InconsistentOffset),
+      ("2024-07-04T15:30:45-04:00[Mars/Olympus_Mons]", 26, ParseErrorKind::UnknownTimeZone),
+      ("2024-07-04T15:30:45-04:00[America/New York]", 26, ParseErrorKind::Unexpected),
+      ("2024-07-04T15:30:45-04:00[!u-ca=iso8601]", 27, ParseErrorKind::Unexpected),
+      (
+        "2024-07-04T15:30:45-04:00[u-ca=iso8601][America/New_York]",
+        40,
+        ParseErrorKind::Unexpected,
+      ),
+      ("2024-07-04T15:30:45-04:00[+04:00:00]", 26, ParseErrorKind::Unexpected),
+      ("2024-07-04T15:30:45-04:00[America/New_York", 42, ParseErrorKind::InputTooShort),
+    ] {
+      let err = parse_lenient(s).unwrap_err();
+      check!(err.index == Some(index), "{s}");
+      check!(err.kind == kind, "{s}");
+    }
+  }
+
+  #[test]
+  fn test_parse_lenient_errors() {
+    for (s, index, kind) in [
+      ("2012-04-21", 10, ParseErrorKind::InputTooShort),
+      ("2012-04-21X11:00:00", 10, ParseErrorKind::Unexpected),
+      ("2012-13-21T11:00:00", 5, ParseErrorKind::InvalidDate),
+      ("2013-02-29T11:00:00", 8, ParseErrorKind::InvalidDate),
+      ("2012-04-21T24:00:00", 11, ParseErrorKind::InvalidTime),
+      ("2012-04-21T11:60:00", 14, ParseErrorKind::InvalidTime),
+      ("2012-04-21T11:00:60", 17, ParseErrorKind::InvalidTime),
+      ("2012-04-21T11:00", 16, ParseErrorKind::InputTooShort),
+      ("2012-04-21T11:00:00.", 20, ParseErrorKind::InputTooShort),
+      ("2012-04-21T11:00:00.x", 20, ParseErrorKind::Unexpected),
+      ("2012-04-21T11:00:00.1234567890", 29, ParseErrorKind::Unexpected),
+      ("2012-04-21T11:00:00+25:00", 19, ParseErrorKind::InvalidTime),
+      ("2012-04-21T11:00:00 UTC", 19, ParseErrorKind::InputTooLong),
+      ("2012-4-21T11:00:00", 6, ParseErrorKind::Unexpected),
+    ] {
+      let err = parse_lenient(s).unwrap_err();
+      check!(err.index == Some(index), "{s}");
+      check!(err.kind == kind, "{s}");
+    }
+  }
+
+  #[test]
+  fn test_to_rfc3339() {
+    let dt = datetime! { 2024-07-04 15:30:45.123456789 };
+    check!(dt.to_rfc3339(Precision::Second) == "2024-07-04T15:30:45Z");
+    check!(dt.to_rfc3339(Precision::Millisecond) == "2024-07-04T15:30:45.123Z");
+    check!(dt.to_rfc3339(Precision::Microsecond) == "2024-07-04T15:30:45.123456Z");
+    check!(dt.to_rfc3339(Precision::Nanosecond) == "2024-07-04T15:30:45.123456789Z");
+    let dt = datetime! { 2024-07-04 15:30:45 };
+    check!(dt.to_rfc3339(Precision::Millisecond) == "2024-07-04T15:30:45.000Z");
+  }
+
+  #[test]
+  fn test_parse_rfc3339() -> ParseResult<()> {
+    let expected = datetime! { 2012-04-21 15:00:00 };
+    for s in [
+      "2012-04-21T15:00:00Z",
+      "2012-04-21t15:00:00z",
+      "2012-04-21T11:00:00-04:00",
+      "2012-04-21T20:30:00+05:30",
+      "2012-04-21T15:00:00-00:00",
+      "2012-04-21T15:00:00+00:00",
+      "2012-04-21T15:00:00.000000000000Z",
+    ] {
+      check!(DateTime::parse_rfc3339(s)? == expected, "{s}");
+    }
+    let dt = DateTime::parse_rfc3339("2012-04-21T15:00:00.1234567891Z")?;
+    check!(dt.nanosecond() == 123_456_789);
+    Ok(())
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_parse_rfc3339_tz() -> ParseResult<()> {
+    use crate::tz::TimeZone;
+    for (s, tz) in [
+      ("2012-04-21T15:00:00Z", TimeZone::Unspecified),
+      ("2012-04-21T15:00:00-00:00", TimeZone::Unspecified),
+      ("2012-04-21T15:00:00+00:00", TimeZone::FixedOffset(0)),
+      ("2012-04-21T11:00:00-04:00", TimeZone::FixedOffset(-14_400)),
+    ] {
+      check!(DateTime::parse_rfc3339(s)?.tz == tz, "{s}");
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_rfc3339_errors() {
+    for (s, index, kind) in [
+      ("2012-04-21 15:00:00Z", 10, ParseErrorKind::Unexpected),
+      ("2012-04-21T15:00:00", 19, ParseErrorKind::InputTooShort),
+      ("2012-04-21T15:00:00-0400", 22, ParseErrorKind::Unexpected),
+      ("2012-04-21T15:00:00-04", 22, ParseErrorKind::InputTooShort),
+      ("2012-04-21T15:00:00.Z", 20, ParseErrorKind::Unexpected),
+      ("2012-04-21T15:00:00+24:00", 19, ParseErrorKind::InvalidTime),
+      ("2012-04-21T15:00:00ZZ", 20, ParseErrorKind::InputTooLong),
+    ] {
+      let err = DateTime::parse_rfc3339(s).unwrap_err();
+      check!(err.index == Some(index), "{s}");
+      check!(err.kind == kind, "{s}");
+    }
+  }
+
+  #[test]
+  fn test_matches_format() {
+    // Walk across a broad range of timestamps and make sure the fast path agrees with the general
+    // format string interpreter.
+    let mut dt = datetime! { 1970-01-01 00:00:00 };
+    let step = TimeInterval::new(86_400 * 37 + 3_727, 123_457_000);
+    let compact =
+      Layout { sep: b'T', extended: true, fraction: Fraction::Micros, offset: Offset::Compact };
+    let mut buf = [0; MAX_LEN];
+    for _ in 0..2_000 {
+      let fmt = match dt.precision() {
+        Precision::Second => "%Y-%m-%d %H:%M:%S",
+        Precision::Millisecond => "%Y-%m-%d %H:%M:%S%.3f",
+        Precision::Microsecond => "%Y-%m-%d %H:%M:%S%.6f",
+        Precision::Nanosecond => "%Y-%m-%d %H:%M:%S%.9f",
+      };
+      check!(dt.layout_str(DEBUG, &mut buf) == dt.format(fmt).to_string());
+      let fmt = match dt.precision() {
+        Precision::Second => "%Y-%m-%dT%H:%M:%S%z",
+        Precision::Millisecond | Precision::Microsecond => "%Y-%m-%dT%H:%M:%S%.6f%z",
+        Precision::Nanosecond => "%Y-%m-%dT%H:%M:%S%.9f%z",
+      };
+      check!(dt.layout_str(compact, &mut buf) == dt.format(fmt).to_string());
+      dt += step;
+    }
+  }
+}
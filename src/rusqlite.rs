@@ -0,0 +1,218 @@
+//! Integration with SQLite using rusqlite
+//!
+//! Dates and times are read from any of the storage formats in [`SqliteFormat`], and written as
+//! UTC TEXT; use [`DateTime::to_sqlite`] to write one in another format.
+//!
+//! [`TimeInterval`]s follow the same conventions: TEXT in the language of [`time_interval!`] (such
+//! as `1h 30m`), REAL as a number of days (the difference of two `julianday()`s), or INTEGER as a
+//! number of seconds (the difference of two `unixepoch()`s).
+//!
+//! [`time_interval!`]: crate::time_interval
+
+use rusqlite::Result;
+use rusqlite::types::FromSql;
+use rusqlite::types::FromSqlError;
+use rusqlite::types::FromSqlResult;
+use rusqlite::types::ToSql;
+use rusqlite::types::ToSqlOutput;
+use rusqlite::types::Value;
+use rusqlite::types::ValueRef;
+
+use crate::DateTime;
+use crate::Precision;
+use crate::PrecisionLoss;
+use crate::interval::TimeInterval;
+
+/// The storage convention used when writing dates, times, and intervals to SQLite.
+///
+/// SQLite has no dedicated date and time storage, but its date and time functions accept all of
+/// these.
+///
+/// ## Example
+///
+/// ```
+/// use datetime::PrecisionLoss;
+/// use datetime::datetime;
+/// use datetime::rusqlite::SqliteFormat;
+/// use rusqlite::Connection;
+///
+/// let conn = Connection::open_in_memory()?;
+/// let dt = datetime! { 2012-04-21 15:00:00.75 };
+/// let value = dt.to_sqlite(SqliteFormat::UnixTime, PrecisionLoss::RoundHalfEven)?;
+/// let stored: i64 = conn.query_row("SELECT ?1", [value], |row| row.get(0))?;
+/// assert_eq!(stored, 1335020401);
+/// # Ok::<(), rusqlite::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SqliteFormat {
+  /// UTC TEXT, such as `2012-04-21 15:00:00.500`, which sorts chronologically.
+  #[default]
+  Text,
+  /// A REAL Julian day number, which carries about ten microseconds of precision.
+  JulianDay,
+  /// INTEGER seconds since the Unix epoch. Any fraction of a second is handled according to the
+  /// policy given to `to_sqlite`.
+  UnixTime,
+}
+
+impl FromSql for DateTime {
+  fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+    match value {
+      ValueRef::Text(_) => value.as_str()?.parse().map_err(|e| FromSqlError::Other(Box::new(e))),
+      ValueRef::Integer(seconds) =>
+        DateTime::from_unix_time(seconds).map_err(|e| FromSqlError::Other(e.into())),
+      ValueRef::Real(julian_day) =>
+        DateTime::from_julian_day(julian_day).map_err(|e| FromSqlError::Other(e.into())),
+      _ => Err(FromSqlError::InvalidType),
+    }
+  }
+}
+
+impl ToSql for DateTime {
+  fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+    self.to_sqlite(SqliteFormat::Text, PrecisionLoss::Truncate)
+  }
+}
+
+impl DateTime {
+  /// Convert this date and time to a SQLite value in the given format, following `policy` for any
+  /// fraction of a second that [`SqliteFormat::UnixTime`] cannot store.
+  pub fn to_sqlite(
+    &self, format: SqliteFormat, policy: PrecisionLoss,
+  ) -> Result<ToSqlOutput<'static>> {
+    Ok(ToSqlOutput::Owned(match format {
+      SqliteFormat::Text => Value::Text(self.sqlite_text()),
+      SqliteFormat::JulianDay => Value::Real(self.julian_day()),
+      SqliteFormat::UnixTime => Value::Integer(
+        self
+          .to_storage_precision(Precision::Second, policy)
+          .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?
+          .as_seconds(),
+      ),
+    }))
+  }
+}
+
+impl FromSql for TimeInterval {
+  fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+    match value {
+      ValueRef::Text(_) => value.as_str()?.parse().map_err(|e| FromSqlError::Other(Box::new(e))),
+      ValueRef::Integer(seconds) => Ok(TimeInterval::new(seconds, 0)),
+      ValueRef::Real(days) => {
+        let nanos = (days * 86_400e9).round();
+        if !nanos.is_finite() || nanos.abs() > i64::MAX as f64 * 1e9 {
+          return Err(FromSqlError::Other(format!("Interval out of range: {days} days").into()));
+        }
+        Ok(TimeInterval::from_nanoseconds(nanos as i128))
+      },
+      _ => Err(FromSqlError::InvalidType),
+    }
+  }
+}
+
+impl ToSql for TimeInterval {
+  fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+    self.to_sqlite(SqliteFormat::Text, PrecisionLoss::Truncate)
+  }
+}
+
+impl TimeInterval {
+  /// Convert this time interval to a SQLite value in the given format, following `policy` for any
+  /// fraction of a second that [`SqliteFormat::UnixTime`] cannot store.
+  pub fn to_sqlite(
+    &self, format: SqliteFormat, policy: PrecisionLoss,
+  ) -> Result<ToSqlOutput<'static>> {
+    Ok(ToSqlOutput::Owned(match format {
+      SqliteFormat::Text => Value::Text(self.to_string()),
+      SqliteFormat::JulianDay => Value::Real(self.as_nanoseconds() as f64 / 86_400e9),
+      // An interval since the Unix epoch rounds the same way as the date and time it reaches.
+      SqliteFormat::UnixTime => Value::Integer(
+        DateTime::from_timestamp(self.seconds(), self.nanoseconds())
+          .to_storage_precision(Precision::Second, policy)
+          .map_err(|_| {
+            rusqlite::Error::ToSqlConversionFailure(
+              format!("{self:?} cannot be stored as whole seconds without losing fidelity").into(),
+            )
+          })?
+          .as_seconds(),
+      ),
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+  use rusqlite::Connection;
+
+  use super::*;
+  use crate::time_interval;
+
+  fn select<T: FromSql>(conn: &Connection, expr: &str) -> Result<T> {
+    conn.query_row(&format!("SELECT {expr}"), [], |row| row.get(0))
+  }
+
+  #[test]
+  fn test_storage_classes() -> FromSqlResult<()> {
+    let expected = datetime! { 2012-04-21 15:00:00 };
+    check!(DateTime::column_result(ValueRef::Text(b"2012-04-21 15:00:00"))? == expected);
+    check!(DateTime::column_result(ValueRef::Real(2456039.125))? == expected);
+    check!(DateTime::column_result(ValueRef::Integer(1335020400))? == expected);
+    check!(DateTime::column_result(ValueRef::Blob(b"\0")).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_formats() -> Result<()> {
+    let conn = Connection::open_in_memory()?;
+    let dt = datetime! { 2012-04-21 15:00:01.5 };
+    let stored = |format, policy| -> Result<(String, DateTime)> {
+      let value = dt.to_sqlite(format, policy)?;
+      conn.query_row("SELECT typeof(?1), ?1", [value], |row| Ok((row.get(0)?, row.get(1)?)))
+    };
+    let plain: (String, DateTime) =
+      conn.query_row("SELECT typeof(?1), ?1", [dt], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    check!(plain == ("text".into(), dt));
+    check!(stored(SqliteFormat::Text, PrecisionLoss::Truncate)? == ("text".into(), dt));
+    check!(stored(SqliteFormat::JulianDay, PrecisionLoss::Truncate)? == ("real".into(), dt));
+    let truncated = datetime! { 2012-04-21 15:00:01 };
+    check!(
+      stored(SqliteFormat::UnixTime, PrecisionLoss::Truncate)? == ("integer".into(), truncated)
+    );
+    let rounded = datetime! { 2012-04-21 15:00:02 };
+    check!(
+      stored(SqliteFormat::UnixTime, PrecisionLoss::RoundHalfEven)? == ("integer".into(), rounded)
+    );
+    check!(stored(SqliteFormat::UnixTime, PrecisionLoss::Error).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_interval() -> Result<()> {
+    let conn = Connection::open_in_memory()?;
+    let interval = time_interval!(1d 1h 30m 0.5s);
+    for format in [SqliteFormat::Text, SqliteFormat::JulianDay, SqliteFormat::UnixTime] {
+      let value = interval.to_sqlite(format, PrecisionLoss::RoundHalfEven)?;
+      let loaded: TimeInterval = conn.query_row("SELECT ?1", [value], |row| row.get(0))?;
+      let expected = match format {
+        SqliteFormat::UnixTime => time_interval!(1d 1h 30m),
+        _ => interval,
+      };
+      check!(loaded == expected, "{format:?}");
+    }
+    let text: String = conn.query_row("SELECT ?1", [interval], |row| row.get(0))?;
+    check!(text == "1d 1h 30m 0.5s");
+
+    let days: TimeInterval =
+      select(&conn, "julianday('2012-04-22 15:00:00') - julianday('2012-04-21 03:00:00')")?;
+    check!(days == time_interval!(1d 12h));
+    let seconds: TimeInterval =
+      select(&conn, "unixepoch('2012-04-21 15:00:00') - unixepoch('2012-04-21 14:58:30')")?;
+    check!(seconds == time_interval!(90s));
+    check!(select::<TimeInterval>(&conn, "'90 minutes'").is_err());
+    check!(select::<TimeInterval>(&conn, "1e300").is_err());
+    let value = time_interval!(0.5s).to_sqlite(SqliteFormat::UnixTime, PrecisionLoss::Error);
+    check!(value.is_err());
+    Ok(())
+  }
+}
@@ -1,26 +1,61 @@
+//! Serialization and deserialization with `serde`.
+//!
+//! [`DateTime`] and [`TimeInterval`] implement `Serialize` and `Deserialize` directly. The
+//! submodules of this module provide alternative representations for use with
+//! `#[serde(with = "...")]`.
+
+use std::borrow::Cow;
 use std::fmt;
 
 use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
 use serde::Serializer;
+use serde::de::Error;
+use serde::de::SeqAccess;
+use serde::de::Unexpected;
 use serde::de::Visitor;
 
 use crate::DateTime;
+use crate::interval::TimeInterval;
+use crate::rfc3339 as layouts;
 
+/// Serialized as a string, such as `"2012-04-21T11:00:00-0400[America/New_York]"`, in
+/// human-readable formats such as JSON; and as a compact `(seconds, nanos, offset, zone)` tuple in
+/// binary formats such as bincode, where `offset` is the UTC offset in seconds and `zone` is the
+/// IANA time zone name, each `None` if absent.
+///
+/// The time zone name is written as an RFC 9557 annotation, so that deserializing yields a date
+/// and time in the same time zone, rather than one with a fixed UTC offset. Consumers that expect
+/// RFC 3339 may reject the annotation; for them, use [`rfc3339`] with `#[serde(with = "...")]`, or
+/// serialize `dt.format("%Y-%m-%dT%H:%M:%S%z")`, since `%z` writes the UTC offset alone.
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 impl Serialize for DateTime {
   fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-    if self.nanos == 0 {
-      serializer.collect_str(&self.format("%Y-%m-%dT%H:%M:%S%z"))
-    } else if self.nanos % 1_000 == 0 {
-      serializer.collect_str(&self.format("%Y-%m-%dT%H:%M:%S%.6f%z"))
-    } else {
-      serializer.collect_str(&self.format("%Y-%m-%dT%H:%M:%S%.9f%z"))
+    if !serializer.is_human_readable() {
+      return (self.seconds, self.nanos, self.layout_offset(), zone_name(self))
+        .serialize(serializer);
+    }
+    let mut buf = [0; layouts::MAX_LEN];
+    let s = self.layout_str(layouts::SERDE, &mut buf);
+    match zone_name(self) {
+      Some(name) => serializer.collect_str(&format_args!("{s}[{name}]")),
+      None => serializer.serialize_str(s),
     }
   }
 }
 
+/// The IANA name of the date and time's time zone, if there is one.
+fn zone_name(dt: &DateTime) -> Option<&'static str> {
+  #[cfg(feature = "tz")]
+  return dt.tz_name();
+  #[cfg(not(feature = "tz"))]
+  {
+    let _ = dt;
+    None
+  }
+}
+
 struct DateTimeVisitor;
 
 impl Visitor<'_> for DateTimeVisitor {
@@ -31,44 +66,1218 @@ impl Visitor<'_> for DateTimeVisitor {
     formatter.write_str("a YYYY-MM-DD HH:MM:SS date string")
   }
 
-  fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+  fn visit_str<E: Error>(self, s: &str) -> Result<Self::Value, E> {
     s.parse().map_err(E::custom)
   }
 }
 
+/// Deserializes the compact `(seconds, nanos, offset, zone)` form used by binary formats.
+struct CompactVisitor;
+
+impl<'de> Visitor<'de> for CompactVisitor {
+  type Value = DateTime;
+
+  #[cfg(not(tarpaulin_include))]
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a (seconds, nanos, offset, zone) tuple")
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let seconds: i64 = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+    let nanos: u32 = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+    if nanos >= 1_000_000_000 {
+      return Err(A::Error::invalid_value(
+        Unexpected::Unsigned(nanos.into()),
+        &"fewer than 1000000000 nanoseconds",
+      ));
+    }
+    let offset: Option<i32> =
+      seq.next_element()?.ok_or_else(|| A::Error::invalid_length(2, &self))?;
+    if let Some(offset) = offset.filter(|o| o.unsigned_abs() >= 86_400) {
+      return Err(A::Error::invalid_value(
+        Unexpected::Signed(offset.into()),
+        &"a UTC offset of less than 24 hours",
+      ));
+    }
+    let zone: Option<Cow<'de, str>> =
+      seq.next_element()?.ok_or_else(|| A::Error::invalid_length(3, &self))?;
+    #[cfg(not(feature = "tz"))]
+    let _ = (offset, zone);
+    Ok(DateTime {
+      seconds,
+      nanos,
+      #[cfg(feature = "tz")]
+      tz: match (offset, zone) {
+        (_, Some(zone)) => {
+          let (name, tz) = crate::tz::by_name(&zone).ok_or_else(|| {
+            A::Error::invalid_value(Unexpected::Str(&zone), &"an IANA time zone")
+          })?;
+          let tz = crate::tz::TimeZone::Named(name, tz);
+          if offset.is_some_and(|offset| tz.ut_offset(seconds).ok() != Some(offset)) {
+            return Err(A::Error::custom(format!(
+              "UTC offset does not match the time zone {name}"
+            )));
+          }
+          tz
+        },
+        (Some(offset), None) => crate::tz::TimeZone::FixedOffset(offset),
+        (None, None) => crate::tz::TimeZone::Unspecified,
+      },
+    })
+  }
+}
+
+/// Deserialized from a string (see [`DateTime`'s `FromStr`
+/// implementation](DateTime#impl-FromStr-for-DateTime)) in human-readable formats, and from the
+/// compact tuple written by `Serialize` in binary formats.
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 impl<'de> Deserialize<'de> for DateTime {
   fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-    deserializer.deserialize_str(DateTimeVisitor)
+    match deserializer.is_human_readable() {
+      true => deserializer.deserialize_str(DateTimeVisitor),
+      false => deserializer.deserialize_tuple(4, CompactVisitor),
+    }
+  }
+}
+
+/// Serialized as a string, such as `"1h 30m"`; see [`TimeInterval`'s `Display`
+/// implementation](TimeInterval#impl-Display-for-TimeInterval).
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl Serialize for TimeInterval {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+struct TimeIntervalVisitor;
+
+impl Visitor<'_> for TimeIntervalVisitor {
+  type Value = TimeInterval;
+
+  #[cfg(not(tarpaulin_include))]
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a duration string, such as \"1h 30m\" or \"PT1H30M\"")
+  }
+
+  fn visit_str<E: Error>(self, s: &str) -> Result<Self::Value, E> {
+    match s.trim_start_matches(['+', '-']).starts_with('P') {
+      true => TimeInterval::parse_iso8601(s).map_err(E::custom),
+      false => s.parse().map_err(E::custom),
+    }
+  }
+}
+
+/// Deserialized from a string, either in the form `"1h 30m"` (see [`TimeInterval`'s `FromStr`
+/// implementation](TimeInterval#impl-FromStr-for-TimeInterval)) or as an ISO 8601 duration (see
+/// [`TimeInterval::parse_iso8601`]).
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de> Deserialize<'de> for TimeInterval {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_str(TimeIntervalVisitor)
+  }
+}
+
+/// Deserializes any integer that fits in an `i128`.
+struct IntegerVisitor;
+
+impl Visitor<'_> for IntegerVisitor {
+  type Value = i128;
+
+  #[cfg(not(tarpaulin_include))]
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("an integer")
+  }
+
+  fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+    Ok(value.into())
+  }
+
+  fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+    Ok(value.into())
+  }
+
+  fn visit_i128<E: Error>(self, value: i128) -> Result<Self::Value, E> {
+    Ok(value)
+  }
+
+  fn visit_u128<E: Error>(self, value: u128) -> Result<Self::Value, E> {
+    i128::try_from(value).map_err(|_| E::custom("integer out of range"))
+  }
+}
+
+/// Alternative representations of [`TimeInterval`], for use with `#[serde(with = "...")]`.
+///
+/// ## Examples
+///
+/// ```
+/// use datetime::interval::TimeInterval;
+/// use serde::Deserialize;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Cache {
+///   #[serde(with = "datetime::serde::interval::seconds")]
+///   ttl: TimeInterval,
+///   #[serde(with = "datetime::serde::interval::milliseconds")]
+///   timeout: TimeInterval,
+/// }
+/// ```
+pub mod interval {
+  use serde::Deserializer;
+  use serde::Serializer;
+  use serde::de::Error;
+  use serde::ser;
+
+  use super::IntegerVisitor;
+  use crate::interval::TimeInterval;
+
+  /// Create a [`TimeInterval`] from nanoseconds, if they are in range.
+  fn from_nanoseconds<E: Error>(nanos: Option<i128>) -> Result<TimeInterval, E> {
+    const MAX: i128 = i64::MAX as i128 * 1_000_000_000;
+    match nanos {
+      Some(nanos) if (-MAX..=MAX).contains(&nanos) => Ok(TimeInterval::from_nanoseconds(nanos)),
+      _ => Err(E::custom("duration out of range")),
+    }
+  }
+
+  /// Serialize an interval as an integer number of the given unit, truncated toward zero.
+  fn serialize_int<S: Serializer>(
+    interval: &TimeInterval, unit: i128, serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    match i64::try_from(interval.as_nanoseconds() / unit) {
+      Ok(value) => serializer.serialize_i64(value),
+      Err(_) => Err(ser::Error::custom("duration out of range")),
+    }
+  }
+
+  /// Deserialize an interval from an integer number of the given unit.
+  fn deserialize_int<'de, D: Deserializer<'de>>(
+    unit: i128, deserializer: D,
+  ) -> Result<TimeInterval, D::Error> {
+    let value = deserializer.deserialize_i64(IntegerVisitor)?;
+    from_nanoseconds(value.checked_mul(unit))
+  }
+
+  macro_rules! integer_module {
+    ($name:ident, $unit:literal, $doc:literal) => {
+      #[doc = concat!("Serialize a [`TimeInterval`] as an integer number of ", $doc, ".")]
+      ///
+      /// Serialization truncates toward zero.
+      pub mod $name {
+        use serde::Deserializer;
+        use serde::Serializer;
+
+        use crate::interval::TimeInterval;
+
+        #[doc = concat!("Serialize an interval as an integer number of ", $doc, ".")]
+        pub fn serialize<S: Serializer>(
+          interval: &TimeInterval, serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+          super::serialize_int(interval, $unit, serializer)
+        }
+
+        #[doc = concat!("Deserialize an interval from an integer number of ", $doc, ".")]
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+          deserializer: D,
+        ) -> Result<TimeInterval, D::Error> {
+          super::deserialize_int($unit, deserializer)
+        }
+      }
+    };
+  }
+
+  integer_module!(seconds, 1_000_000_000, "seconds");
+  integer_module!(milliseconds, 1_000_000, "milliseconds");
+  integer_module!(nanoseconds, 1, "nanoseconds");
+
+  /// Serialize a [`TimeInterval`] as a floating-point number of seconds.
+  ///
+  /// Deserialization rounds to the nearest nanosecond, and also accepts integers.
+  pub mod seconds_float {
+    use std::fmt;
+
+    use serde::Deserializer;
+    use serde::Serializer;
+    use serde::de::Error;
+    use serde::de::Visitor;
+
+    use crate::interval::TimeInterval;
+
+    /// Serialize an interval as a floating-point number of seconds.
+    pub fn serialize<S: Serializer>(
+      interval: &TimeInterval, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+      serializer.serialize_f64(interval.as_nanoseconds() as f64 / 1_000_000_000.0)
+    }
+
+    /// Deserialize an interval from a floating-point number of seconds.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+      deserializer: D,
+    ) -> Result<TimeInterval, D::Error> {
+      deserializer.deserialize_f64(FloatVisitor)
+    }
+
+    struct FloatVisitor;
+
+    impl Visitor<'_> for FloatVisitor {
+      type Value = TimeInterval;
+
+      #[cfg(not(tarpaulin_include))]
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number of seconds")
+      }
+
+      fn visit_f64<E: Error>(self, value: f64) -> Result<Self::Value, E> {
+        let nanos = (value * 1_000_000_000.0).round();
+        super::from_nanoseconds(nanos.is_finite().then_some(nanos as i128))
+      }
+
+      fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+        super::from_nanoseconds((value as i128).checked_mul(1_000_000_000))
+      }
+
+      fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+        super::from_nanoseconds((value as i128).checked_mul(1_000_000_000))
+      }
+    }
+  }
+
+  /// Serialize a [`TimeInterval`] as an ISO 8601 duration string, such as `"PT1H30M"`.
+  ///
+  /// See [`TimeInterval::to_iso8601`] and [`TimeInterval::parse_iso8601`].
+  pub mod iso8601 {
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+    use serde::de::Error;
+
+    use crate::interval::TimeInterval;
+
+    /// Serialize an interval as an ISO 8601 duration string.
+    pub fn serialize<S: Serializer>(
+      interval: &TimeInterval, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+      serializer.serialize_str(&interval.to_iso8601())
+    }
+
+    /// Deserialize an interval from an ISO 8601 duration string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+      deserializer: D,
+    ) -> Result<TimeInterval, D::Error> {
+      let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+      TimeInterval::parse_iso8601(s).map_err(D::Error::custom)
+    }
+  }
+
+  /// Serialize a [`TimeInterval`] as a string such as `"1h 30m"`.
+  ///
+  /// This is the language used by [`time_interval!`](crate::time_interval), with the additional
+  /// units `ms`, `us`, and `ns` accepted when deserializing. Unlike the default implementation, it
+  /// does not accept ISO 8601 durations.
+  pub mod humantime {
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+    use serde::de::Error;
+
+    use crate::interval::TimeInterval;
+
+    /// Serialize an interval as a string such as `"1h 30m"`.
+    pub fn serialize<S: Serializer>(
+      interval: &TimeInterval, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+      serializer.collect_str(interval)
+    }
+
+    /// Deserialize an interval from a string such as `"1h 30m"`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+      deserializer: D,
+    ) -> Result<TimeInterval, D::Error> {
+      let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+      s.parse().map_err(D::Error::custom)
+    }
+  }
+}
+
+/// Generate an `option` submodule that wraps the enclosing module's `serialize` and
+/// `deserialize` functions for `Option<DateTime>`.
+macro_rules! option_module {
+  () => {
+    /// The same representation for an `Option<DateTime>`, with `None` represented as a null
+    /// value.
+    pub mod option {
+      use std::fmt;
+
+      use serde::Deserializer;
+      use serde::Serialize;
+      use serde::Serializer;
+      use serde::de::Error;
+      use serde::de::Visitor;
+
+      use crate::DateTime;
+
+      /// Serialize an optional date and time.
+      pub fn serialize<S: Serializer>(
+        dt: &Option<DateTime>, serializer: S,
+      ) -> Result<S::Ok, S::Error> {
+        match dt {
+          Some(dt) => serializer.serialize_some(&With(dt)),
+          None => serializer.serialize_none(),
+        }
+      }
+
+      /// Deserialize an optional date and time.
+      pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+      ) -> Result<Option<DateTime>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor)
+      }
+
+      struct With<'a>(&'a DateTime);
+
+      impl Serialize for With<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+          super::serialize(self.0, serializer)
+        }
+      }
+
+      struct OptionVisitor;
+
+      impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<DateTime>;
+
+        #[cfg(not(tarpaulin_include))]
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+          formatter.write_str("an optional date and time")
+        }
+
+        fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+          Ok(None)
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+          Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+          self, deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+          super::deserialize(deserializer).map(Some)
+        }
+      }
+    }
+  };
+}
+
+/// Create a [`DateTime`] from nanoseconds since the Unix epoch, if they are in range.
+fn from_timestamp_nanos<E: Error>(nanos: Option<i128>) -> Result<DateTime, E> {
+  const MAX: i128 = i64::MAX as i128 * 1_000_000_000;
+  match nanos {
+    Some(nanos) if (-MAX..=MAX).contains(&nanos) => Ok(DateTime::from_timestamp_nanos(nanos)),
+    _ => Err(E::custom("timestamp out of range")),
+  }
+}
+
+/// Generate a module that serializes a [`DateTime`] as an integer number of the given unit since
+/// the Unix epoch.
+macro_rules! timestamp_module {
+  ($name:ident, $unit:literal, $doc:literal) => {
+    #[doc = concat!("Serialize a [`DateTime`] as an integer number of ", $doc, " since the Unix epoch.")]
+    ///
+    /// Serialization rounds down, and discards the time zone; deserialization yields a date and time
+    /// with no time zone.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use datetime::DateTime;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Event {
+    #[doc = concat!("  #[serde(with = \"datetime::serde::", stringify!($name), "\")]")]
+    ///   at: DateTime,
+    #[doc = concat!("  #[serde(with = \"datetime::serde::", stringify!($name), "::option\")]")]
+    ///   until: Option<DateTime>,
+    /// }
+    /// ```
+    pub mod $name {
+      use serde::Deserializer;
+      use serde::Serializer;
+      use serde::ser::Error;
+
+      use crate::DateTime;
+
+      #[doc = concat!("Serialize a date and time as an integer number of ", $doc, " since the Unix epoch.")]
+      pub fn serialize<S: Serializer>(dt: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        match i64::try_from(dt.as_nanoseconds().div_euclid($unit)) {
+          Ok(value) => serializer.serialize_i64(value),
+          Err(_) => Err(S::Error::custom("timestamp out of range")),
+        }
+      }
+
+      #[doc = concat!("Deserialize a date and time from an integer number of ", $doc, " since the Unix epoch.")]
+      pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+        let value = deserializer.deserialize_i64(super::IntegerVisitor)?;
+        super::from_timestamp_nanos(value.checked_mul($unit))
+      }
+
+      option_module!();
+    }
+  };
+}
+
+timestamp_module!(ts_seconds, 1_000_000_000, "seconds");
+timestamp_module!(ts_milliseconds, 1_000_000, "milliseconds");
+timestamp_module!(ts_microseconds, 1_000, "microseconds");
+timestamp_module!(ts_nanoseconds, 1, "nanoseconds");
+
+/// Serialize a [`DateTime`] as a floating-point number of seconds since the Unix epoch.
+///
+/// Serialization discards the time zone, and may lose precision; deserialization rounds to the
+/// nearest nanosecond, accepts integers, and yields a date and time with no time zone.
+pub mod ts_seconds_float {
+  use std::fmt;
+
+  use serde::Deserializer;
+  use serde::Serializer;
+  use serde::de::Error;
+  use serde::de::Visitor;
+
+  use crate::DateTime;
+
+  /// Serialize a date and time as a floating-point number of seconds since the Unix epoch.
+  pub fn serialize<S: Serializer>(dt: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(dt.as_seconds() as f64 + dt.nanosecond() as f64 / 1_000_000_000.0)
+  }
+
+  /// Deserialize a date and time from a floating-point number of seconds since the Unix epoch.
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+    deserializer.deserialize_f64(FloatVisitor)
+  }
+
+  struct FloatVisitor;
+
+  impl Visitor<'_> for FloatVisitor {
+    type Value = DateTime;
+
+    #[cfg(not(tarpaulin_include))]
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+      formatter.write_str("a number of seconds since the Unix epoch")
+    }
+
+    fn visit_f64<E: Error>(self, value: f64) -> Result<Self::Value, E> {
+      let nanos = (value * 1_000_000_000.0).round();
+      super::from_timestamp_nanos(nanos.is_finite().then_some(nanos as i128))
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+      super::from_timestamp_nanos((value as i128).checked_mul(1_000_000_000))
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+      super::from_timestamp_nanos((value as i128).checked_mul(1_000_000_000))
+    }
+  }
+
+  option_module!();
+}
+
+/// Serialize a [`DateTime`] as an RFC 3339 string, such as `"2012-04-21T11:00:00-04:00"`.
+///
+/// Unlike the default representation, this always includes a UTC offset (`Z` for a date and time
+/// with no time zone), and deserialization is strict; see [`DateTime::parse_rfc3339`].
+pub mod rfc3339 {
+  use serde::Deserialize;
+  use serde::Deserializer;
+  use serde::Serializer;
+  use serde::de::Error;
+
+  use crate::DateTime;
+  use crate::rfc3339 as layouts;
+
+  /// Serialize a date and time as an RFC 3339 string.
+  pub fn serialize<S: Serializer>(dt: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut buf = [0; layouts::MAX_LEN];
+    serializer.serialize_str(dt.layout_str(layouts::RFC3339, &mut buf))
+  }
+
+  /// Deserialize a date and time from an RFC 3339 string.
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+    let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+    DateTime::parse_rfc3339(s).map_err(D::Error::custom)
+  }
+
+  option_module!();
+}
+
+/// Serialize a [`DateTime`] as an RFC 2822 string, such as `"Sat, 21 Apr 2012 11:00:00 -0400"`.
+///
+/// Serialization discards fractional seconds; see [`DateTime::to_rfc2822`] and
+/// [`DateTime::parse_rfc2822`].
+pub mod rfc2822 {
+  use serde::Deserialize;
+  use serde::Deserializer;
+  use serde::Serializer;
+  use serde::de::Error;
+
+  use crate::DateTime;
+
+  /// Serialize a date and time as an RFC 2822 string.
+  pub fn serialize<S: Serializer>(dt: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&dt.to_rfc2822())
+  }
+
+  /// Deserialize a date and time from an RFC 2822 string.
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+    let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+    DateTime::parse_rfc2822(s).map_err(D::Error::custom)
+  }
+
+  option_module!();
+}
+
+/// Generate a submodule of [`lenient`] that reads bare numbers in the given unit.
+macro_rules! lenient_module {
+  ($name:ident, $unit:literal, $doc:literal) => {
+    #[doc = concat!("Lenient deserialization that reads bare numbers as ", $doc, " since the Unix epoch.")]
+    pub mod $name {
+      use serde::Deserializer;
+      use serde::Serializer;
+
+      use crate::DateTime;
+
+      /// Serialize a date and time in the default representation.
+      pub fn serialize<S: Serializer>(dt: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize(dt, serializer)
+      }
+
+      #[doc = concat!("Deserialize a date and time from a string, a number of ", $doc, " since the Unix epoch, or a map.")]
+      pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+        deserializer.deserialize_any(super::LenientVisitor { unit: $unit, unit_name: $doc })
+      }
+
+      option_module!();
+    }
+  };
+}
+
+/// Lenient deserialization of a [`DateTime`] from whatever shape an external API happens to send.
+///
+/// The default `Deserialize` implementation only accepts an ISO 8601 / RFC 3339 string. This
+/// module additionally accepts:
+///
+/// - RFC 2822 strings, such as `"Sat, 21 Apr 2012 11:00:00 -0400"`;
+/// - integers and floats, as a number of seconds since the Unix epoch (the
+///   [`milliseconds`](lenient::milliseconds), [`microseconds`](lenient::microseconds), and
+///   [`nanoseconds`](lenient::nanoseconds) submodules use other units);
+/// - maps in the style of `google.protobuf.Timestamp`, such as `{ "seconds": 1335006000, "nanos":
+///   500000000 }`, where `nanos` is optional.
+///
+/// Anything else is an error that lists these shapes. Serialization uses the default
+/// representation. This relies on `Deserializer::deserialize_any`, so it only works with
+/// self-describing formats, such as JSON.
+///
+/// ## Examples
+///
+/// ```
+/// use datetime::DateTime;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///   // Accepts `1335006000500`, `"2012-04-21T11:00:00.5Z"`, and `{ "seconds": 1335006000 }`.
+///   #[serde(with = "datetime::serde::lenient::milliseconds")]
+///   at: DateTime,
+/// }
+/// ```
+pub mod lenient {
+  use std::borrow::Cow;
+  use std::fmt;
+
+  use serde::Deserializer;
+  use serde::Serialize;
+  use serde::Serializer;
+  use serde::de::Error;
+  use serde::de::MapAccess;
+  use serde::de::Unexpected;
+  use serde::de::Visitor;
+
+  use crate::DateTime;
+
+  /// Serialize a date and time in the default representation.
+  pub fn serialize<S: Serializer>(dt: &DateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    dt.serialize(serializer)
+  }
+
+  /// Deserialize a date and time from a string, a number of seconds since the Unix epoch, or a
+  /// map.
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime, D::Error> {
+    deserializer.deserialize_any(LenientVisitor { unit: 1_000_000_000, unit_name: "seconds" })
+  }
+
+  option_module!();
+  lenient_module!(milliseconds, 1_000_000, "milliseconds");
+  lenient_module!(microseconds, 1_000, "microseconds");
+  lenient_module!(nanoseconds, 1, "nanoseconds");
+
+  const FIELDS: &[&str] = &["seconds", "nanos"];
+
+  struct LenientVisitor {
+    /// The number of nanoseconds in one unit of a bare number.
+    unit: i128,
+    unit_name: &'static str,
+  }
+
+  impl LenientVisitor {
+    fn scale<E: Error>(&self, value: i128) -> Result<DateTime, E> {
+      super::from_timestamp_nanos(value.checked_mul(self.unit))
+    }
+  }
+
+  impl<'de> Visitor<'de> for LenientVisitor {
+    type Value = DateTime;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+      write!(
+        formatter,
+        "an RFC 3339 or RFC 2822 string, a number of {} since the Unix epoch, or a map with \
+         `seconds` and `nanos`",
+        self.unit_name
+      )
+    }
+
+    fn visit_str<E: Error>(self, s: &str) -> Result<Self::Value, E> {
+      s.parse()
+        .or_else(|_| DateTime::parse_rfc2822(s))
+        .map_err(|_| E::invalid_value(Unexpected::Str(s), &self))
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+      self.scale(value.into())
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+      self.scale(value.into())
+    }
+
+    fn visit_i128<E: Error>(self, value: i128) -> Result<Self::Value, E> {
+      self.scale(value)
+    }
+
+    fn visit_u128<E: Error>(self, value: u128) -> Result<Self::Value, E> {
+      self.scale(i128::try_from(value).map_err(|_| E::custom("timestamp out of range"))?)
+    }
+
+    fn visit_f64<E: Error>(self, value: f64) -> Result<Self::Value, E> {
+      let nanos = (value * self.unit as f64).round();
+      super::from_timestamp_nanos(nanos.is_finite().then_some(nanos as i128))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+      let (mut seconds, mut nanos) = (None, None);
+      while let Some(key) = map.next_key::<Cow<'de, str>>()? {
+        match key.as_ref() {
+          "seconds" if seconds.is_some() => return Err(A::Error::duplicate_field("seconds")),
+          "seconds" => seconds = Some(map.next_value::<i64>()?),
+          "nanos" if nanos.is_some() => return Err(A::Error::duplicate_field("nanos")),
+          "nanos" => nanos = Some(map.next_value::<u32>()?),
+          other => return Err(A::Error::unknown_field(other, FIELDS)),
+        }
+      }
+      let seconds = seconds.ok_or_else(|| A::Error::missing_field("seconds"))?;
+      let nanos = nanos.unwrap_or(0);
+      if nanos >= 1_000_000_000 {
+        return Err(A::Error::invalid_value(
+          Unexpected::Unsigned(nanos.into()),
+          &"fewer than 1000000000 nanoseconds",
+        ));
+      }
+      Ok(DateTime::from_timestamp(seconds, nanos))
+    }
   }
 }
 
 #[cfg(test)]
 mod tests {
+  use serde::Deserialize;
+  use serde::Serialize;
+  use serde_test::Configure;
   use serde_test::Token;
+  use serde_test::assert_de_tokens;
+  use serde_test::assert_de_tokens_error;
   use serde_test::assert_tokens;
 
   use crate::DateTime;
+  use crate::interval::TimeInterval;
+  use crate::time_interval;
 
   #[test]
   fn test_serde() {
-    assert_tokens(&datetime! { 2012-04-21 11:00:00 }, &[Token::Str("2012-04-21T11:00:00")]);
-    assert_tokens(&DateTime::ymd(2024, 7, 4).hms(15, 30, 45).nanos(123_456_000).build(), &[
-      Token::Str("2024-07-04T15:30:45.123456"),
-    ]);
-    assert_tokens(&DateTime::ymd(2024, 7, 4).hms(15, 30, 45).nanos(123_456_789).build(), &[
-      Token::Str("2024-07-04T15:30:45.123456789"),
-    ]);
+    assert_tokens(&datetime! { 2012-04-21 11:00:00 }.readable(), &[Token::Str(
+      "2012-04-21T11:00:00",
+    )]);
+    assert_tokens(
+      &DateTime::ymd(2024, 7, 4).hms(15, 30, 45).nanos(123_456_000).build().readable(),
+      &[Token::Str("2024-07-04T15:30:45.123456")],
+    );
+    assert_tokens(
+      &DateTime::ymd(2024, 7, 4).hms(15, 30, 45).nanos(123_456_789).build().readable(),
+      &[Token::Str("2024-07-04T15:30:45.123456789")],
+    );
   }
 
   #[cfg(feature = "tz")]
   #[test]
   fn test_serde_tz() {
-    assert_tokens(&datetime! { 2012-04-21 11:00:00 us::EASTERN }, &[Token::Str(
-      "2012-04-21T11:00:00-0400",
+    assert_tokens(&datetime! { 2012-04-21 11:00:00 america::NEW_YORK }.readable(), &[Token::Str(
+      "2012-04-21T11:00:00-0400[America/New_York]",
     )]);
-    assert_tokens(&datetime! { 2012-04-21 11:00:00 europe::BERLIN }, &[Token::Str(
-      "2012-04-21T11:00:00+0200",
+    assert_tokens(&datetime! { 2012-04-21 11:00:00 europe::BERLIN }.readable(), &[Token::Str(
+      "2012-04-21T11:00:00+0200[Europe/Berlin]",
     )]);
+    let fixed: DateTime = "2012-04-21T11:00:00-04:00".parse().unwrap();
+    assert_tokens(&fixed.readable(), &[Token::Str("2012-04-21T11:00:00-0400")]);
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_serde_tz_round_trip() {
+    use assert2::check;
+
+    // Time zones that share rules with another keep their own name.
+    let dt = datetime! { 2012-04-21 11:00:00 europe::ZURICH };
+    assert_tokens(&dt.readable(), &[Token::Str("2012-04-21T11:00:00+0200[Europe/Zurich]")]);
+    let dt: DateTime = "2012-04-21T11:00:00+0200[Europe/Zurich]".parse().unwrap();
+    check!(dt.tz_name() == Some("Europe/Zurich"));
+
+    // The time zone survives a round trip, so arithmetic across a DST transition stays correct.
+    let dt = datetime! { 2024-03-09 12:00:00 america::NEW_YORK };
+    let tokens = [Token::Str("2024-03-09T12:00:00-0500[America/New_York]")];
+    assert_tokens(&dt.readable(), &tokens);
+    let dt: DateTime = "2024-03-09T12:00:00-0500[America/New_York]".parse().unwrap();
+    check!(dt.tz_name() == Some("America/New_York"));
+    check!((dt + time_interval!(24h)).format("%H:%M %z").to_string() == "13:00 -0400");
+
+    assert_de_tokens_error::<serde_test::Readable<DateTime>>(
+      &[Token::Str("2024-03-10T12:00:00-0500[America/New_York]")],
+      "2024-03-10T12:00:00-0500[America/New_York]\n                   ^-----\nUTC offset does \
+       not match the time zone",
+    );
+  }
+
+  #[test]
+  fn test_serde_interval() {
+    assert_tokens(&time_interval!(1h 30m), &[Token::Str("1h 30m")]);
+    assert_tokens(&time_interval!(-0.5s), &[Token::Str("-0.5s")]);
+    assert_de_tokens(&time_interval!(1h 30m), &[Token::Str("PT1H30M")]);
+    assert_de_tokens(&time_interval!(-0.5s), &[Token::Str("-PT0.5S")]);
+    assert_de_tokens(&TimeInterval::from_milliseconds(250), &[Token::Str("250ms")]);
+    assert_de_tokens_error::<TimeInterval>(
+      &[Token::Str("P1M")],
+      "P1M\n ^-----\nYears and months cannot be converted to an exact duration",
+    );
+  }
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Intervals {
+    #[serde(with = "crate::serde::interval::seconds")]
+    secs: TimeInterval,
+    #[serde(with = "crate::serde::interval::milliseconds")]
+    millis: TimeInterval,
+    #[serde(with = "crate::serde::interval::nanoseconds")]
+    nanos: TimeInterval,
+    #[serde(with = "crate::serde::interval::seconds_float")]
+    float: TimeInterval,
+    #[serde(with = "crate::serde::interval::iso8601")]
+    iso: TimeInterval,
+    #[serde(with = "crate::serde::interval::humantime")]
+    human: TimeInterval,
+  }
+
+  #[test]
+  fn test_serde_interval_with() {
+    let intervals = Intervals {
+      secs: time_interval!(-90s),
+      millis: TimeInterval::from_milliseconds(1_500),
+      nanos: TimeInterval::from_nanoseconds(-1),
+      float: time_interval!(2.25s),
+      iso: time_interval!(36h),
+      human: time_interval!(1d 4.5s),
+    };
+    let tokens = |secs, millis| {
+      [
+        Token::Struct { name: "Intervals", len: 6 },
+        Token::Str("secs"),
+        secs,
+        Token::Str("millis"),
+        millis,
+        Token::Str("nanos"),
+        Token::I64(-1),
+        Token::Str("float"),
+        Token::F64(2.25),
+        Token::Str("iso"),
+        Token::Str("PT36H"),
+        Token::Str("human"),
+        Token::Str("1d 4.5s"),
+        Token::StructEnd,
+      ]
+    };
+    assert_tokens(&intervals, &tokens(Token::I64(-90), Token::I64(1_500)));
+    assert_de_tokens(&intervals, &tokens(Token::I8(-90), Token::U64(1_500)));
+  }
+
+  #[test]
+  fn test_serde_interval_with_truncation() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Seconds(#[serde(with = "crate::serde::interval::seconds")] TimeInterval);
+
+    serde_test::assert_ser_tokens(&Seconds(time_interval!(-1.5s)), &[
+      Token::NewtypeStruct { name: "Seconds" },
+      Token::I64(-1),
+    ]);
+    assert_de_tokens_error::<Seconds>(
+      &[Token::NewtypeStruct { name: "Seconds" }, Token::U64(u64::MAX)],
+      "duration out of range",
+    );
+  }
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Timestamps {
+    #[serde(with = "crate::serde::ts_seconds")]
+    secs: DateTime,
+    #[serde(with = "crate::serde::ts_milliseconds")]
+    millis: DateTime,
+    #[serde(with = "crate::serde::ts_microseconds")]
+    micros: DateTime,
+    #[serde(with = "crate::serde::ts_nanoseconds")]
+    nanos: DateTime,
+    #[serde(with = "crate::serde::ts_seconds_float")]
+    float: DateTime,
+    #[serde(with = "crate::serde::rfc3339")]
+    rfc3339: DateTime,
+    #[serde(with = "crate::serde::rfc2822")]
+    rfc2822: DateTime,
+  }
+
+  #[test]
+  fn test_serde_with() {
+    let dt = datetime! { 2012-04-21 11:00:00.5 };
+    let timestamps = Timestamps {
+      secs: datetime! { 2012-04-21 11:00:00 },
+      millis: dt,
+      micros: dt,
+      nanos: dt,
+      float: dt,
+      rfc3339: dt,
+      rfc2822: datetime! { 2012-04-21 11:00:00 },
+    };
+    let tokens = |secs, millis| {
+      [
+        Token::Struct { name: "Timestamps", len: 7 },
+        Token::Str("secs"),
+        secs,
+        Token::Str("millis"),
+        millis,
+        Token::Str("micros"),
+        Token::I64(1_335_006_000_500_000),
+        Token::Str("nanos"),
+        Token::I64(1_335_006_000_500_000_000),
+        Token::Str("float"),
+        Token::F64(1_335_006_000.5),
+        Token::Str("rfc3339"),
+        Token::Str("2012-04-21T11:00:00.500Z"),
+        Token::Str("rfc2822"),
+        Token::Str("Sat, 21 Apr 2012 11:00:00 +0000"),
+        Token::StructEnd,
+      ]
+    };
+    assert_tokens(&timestamps, &tokens(Token::I64(1_335_006_000), Token::I64(1_335_006_000_500)));
+    assert_de_tokens(
+      &timestamps,
+      &tokens(Token::U32(1_335_006_000), Token::U64(1_335_006_000_500)),
+    );
+  }
+
+  #[test]
+  fn test_serde_with_rounding() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Seconds(#[serde(with = "crate::serde::ts_seconds")] DateTime);
+
+    serde_test::assert_ser_tokens(&Seconds(datetime! { 1969-12-31 23:59:59.5 }), &[
+      Token::NewtypeStruct { name: "Seconds" },
+      Token::I64(-1),
+    ]);
+    assert_de_tokens(&Seconds(datetime! { 1969-12-31 23:59:59 }), &[
+      Token::NewtypeStruct { name: "Seconds" },
+      Token::I64(-1),
+    ]);
+    assert_de_tokens_error::<Seconds>(
+      &[Token::NewtypeStruct { name: "Seconds" }, Token::Str("2012-04-21")],
+      "invalid type: string \"2012-04-21\", expected an integer",
+    );
+  }
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Optional {
+    #[serde(with = "crate::serde::ts_milliseconds::option")]
+    millis: Option<DateTime>,
+    #[serde(with = "crate::serde::rfc3339::option")]
+    rfc3339: Option<DateTime>,
+  }
+
+  #[test]
+  fn test_serde_with_option() {
+    let dt = datetime! { 2012-04-21 11:00:00 };
+    assert_tokens(&Optional { millis: Some(dt), rfc3339: None }, &[
+      Token::Struct { name: "Optional", len: 2 },
+      Token::Str("millis"),
+      Token::Some,
+      Token::I64(1_335_006_000_000),
+      Token::Str("rfc3339"),
+      Token::None,
+      Token::StructEnd,
+    ]);
+    assert_tokens(&Optional { millis: None, rfc3339: Some(dt) }, &[
+      Token::Struct { name: "Optional", len: 2 },
+      Token::Str("millis"),
+      Token::None,
+      Token::Str("rfc3339"),
+      Token::Some,
+      Token::Str("2012-04-21T11:00:00Z"),
+      Token::StructEnd,
+    ]);
+    assert_de_tokens(&Optional { millis: None, rfc3339: None }, &[
+      Token::Struct { name: "Optional", len: 2 },
+      Token::Str("millis"),
+      Token::Unit,
+      Token::Str("rfc3339"),
+      Token::None,
+      Token::StructEnd,
+    ]);
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_serde_with_tz() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Strings {
+      #[serde(with = "crate::serde::rfc3339")]
+      rfc3339: DateTime,
+      #[serde(with = "crate::serde::rfc2822")]
+      rfc2822: DateTime,
+    }
+
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    serde_test::assert_ser_tokens(&Strings { rfc3339: dt, rfc2822: dt }, &[
+      Token::Struct { name: "Strings", len: 2 },
+      Token::Str("rfc3339"),
+      Token::Str("2012-04-21T11:00:00-04:00"),
+      Token::Str("rfc2822"),
+      Token::Str("Sat, 21 Apr 2012 11:00:00 -0400"),
+      Token::StructEnd,
+    ]);
+  }
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Lenient {
+    #[serde(with = "crate::serde::lenient")]
+    secs: DateTime,
+    #[serde(with = "crate::serde::lenient::milliseconds")]
+    millis: DateTime,
+    #[serde(with = "crate::serde::lenient::nanoseconds::option")]
+    nanos: Option<DateTime>,
+  }
+
+  #[test]
+  fn test_serde_lenient() {
+    let dt = datetime! { 2012-04-21 11:00:00.5 };
+    let expected = Lenient { secs: dt, millis: dt, nanos: Some(dt) };
+    for (secs, millis, nanos) in [
+      (
+        Token::F64(1_335_006_000.5),
+        Token::I64(1_335_006_000_500),
+        Token::U64(1_335_006_000_500_000_000),
+      ),
+      (
+        Token::Str("2012-04-21T11:00:00.5Z"),
+        Token::F64(1_335_006_000_500.0),
+        Token::Str("2012-04-21 11:00:00.5"),
+      ),
+    ] {
+      assert_de_tokens(&expected, &[
+        Token::Struct { name: "Lenient", len: 3 },
+        Token::Str("secs"),
+        secs,
+        Token::Str("millis"),
+        millis,
+        Token::Str("nanos"),
+        Token::Some,
+        nanos,
+        Token::StructEnd,
+      ]);
+    }
+
+    let dt = datetime! { 2012-04-21 11:00:00 };
+    assert_de_tokens(&Lenient { secs: dt, millis: dt, nanos: None }, &[
+      Token::Struct { name: "Lenient", len: 3 },
+      Token::Str("secs"),
+      Token::Str("Sat, 21 Apr 2012 11:00:00 GMT"),
+      Token::Str("millis"),
+      Token::Map { len: Some(1) },
+      Token::Str("seconds"),
+      Token::I64(1_335_006_000),
+      Token::MapEnd,
+      Token::Str("nanos"),
+      Token::None,
+      Token::StructEnd,
+    ]);
+  }
+
+  #[test]
+  fn test_serde_lenient_map() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Proto(#[serde(with = "crate::serde::lenient")] DateTime);
+
+    // Errors stop deserialization partway through, so each case lists only the tokens consumed.
+    let map = |entries: &[Token]| {
+      let mut tokens = vec![Token::NewtypeStruct { name: "Proto" }, Token::Map { len: None }];
+      tokens.extend_from_slice(entries);
+      tokens
+    };
+    assert_de_tokens(
+      &Proto(datetime! { 1969-12-31 23:59:59.75 }),
+      &map(&[
+        Token::Str("nanos"),
+        Token::U32(750_000_000),
+        Token::Str("seconds"),
+        Token::I64(-1),
+        Token::MapEnd,
+      ]),
+    );
+    for (entries, message) in [
+      (&[Token::Str("nanos"), Token::U32(0), Token::MapEnd][..], "missing field `seconds`"),
+      (&[Token::Str("secs")], "unknown field `secs`, expected `seconds` or `nanos`"),
+      (
+        &[Token::Str("seconds"), Token::I64(0), Token::Str("seconds")],
+        "duplicate field `seconds`",
+      ),
+      (
+        &[
+          Token::Str("seconds"),
+          Token::I64(0),
+          Token::Str("nanos"),
+          Token::U32(1_000_000_000),
+          Token::MapEnd,
+        ],
+        "invalid value: integer `1000000000`, expected fewer than 1000000000 nanoseconds",
+      ),
+    ] {
+      assert_de_tokens_error::<Proto>(&map(entries), message);
+    }
+  }
+
+  #[test]
+  fn test_serde_lenient_errors() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Millis(#[serde(with = "crate::serde::lenient::milliseconds")] DateTime);
+
+    let expected = "an RFC 3339 or RFC 2822 string, a number of milliseconds since the Unix \
+                    epoch, or a map with `seconds` and `nanos`";
+    assert_de_tokens_error::<Millis>(
+      &[Token::NewtypeStruct { name: "Millis" }, Token::Str("yesterday")],
+      &format!("invalid value: string \"yesterday\", expected {expected}"),
+    );
+    assert_de_tokens_error::<Millis>(
+      &[Token::NewtypeStruct { name: "Millis" }, Token::Bool(true)],
+      &format!("invalid type: boolean `true`, expected {expected}"),
+    );
+    assert_de_tokens_error::<Millis>(
+      &[Token::NewtypeStruct { name: "Millis" }, Token::F64(f64::INFINITY)],
+      "timestamp out of range",
+    );
+  }
+
+  #[test]
+  fn test_serde_compact() {
+    let dt = DateTime::ymd(2024, 7, 4).hms(15, 30, 45).nanos(123_456_789).build();
+    assert_tokens(&dt.compact(), &[
+      Token::Tuple { len: 4 },
+      Token::I64(1_720_107_045),
+      Token::U32(123_456_789),
+      Token::None,
+      Token::None,
+      Token::TupleEnd,
+    ]);
+    assert_tokens(&datetime! { 1969-12-31 23:59:59.5 }.compact(), &[
+      Token::Tuple { len: 4 },
+      Token::I64(-1),
+      Token::U32(500_000_000),
+      Token::None,
+      Token::None,
+      Token::TupleEnd,
+    ]);
+  }
+
+  #[test]
+  fn test_serde_compact_errors() {
+    for (tokens, message) in [
+      (
+        &[Token::Tuple { len: 4 }, Token::I64(0), Token::U32(1_000_000_000)][..],
+        "invalid value: integer `1000000000`, expected fewer than 1000000000 nanoseconds",
+      ),
+      (
+        &[Token::Tuple { len: 4 }, Token::I64(0), Token::U32(0), Token::Some, Token::I32(86_400)],
+        "invalid value: integer `86400`, expected a UTC offset of less than 24 hours",
+      ),
+      (
+        &[Token::Tuple { len: 4 }, Token::I64(0), Token::TupleEnd],
+        "invalid length 1, expected a (seconds, nanos, offset, zone) tuple",
+      ),
+      (
+        &[Token::Str("2012-04-21T11:00:00")],
+        "invalid type: string \"2012-04-21T11:00:00\", expected a (seconds, nanos, offset, zone) \
+         tuple",
+      ),
+    ] {
+      serde_test::assert_de_tokens_error::<serde_test::Compact<DateTime>>(tokens, message);
+    }
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_serde_compact_tz() {
+    let tokens = |zone| {
+      [
+        Token::Tuple { len: 4 },
+        Token::I64(1_335_020_400),
+        Token::U32(0),
+        Token::Some,
+        Token::I32(-14_400),
+        zone,
+        Token::TupleEnd,
+      ]
+    };
+    let dt = datetime! { 2012-04-21 11:00:00 america::NEW_YORK };
+    assert_tokens(&dt.compact(), &{
+      let mut tokens = tokens(Token::Some).to_vec();
+      tokens.insert(6, Token::Str("America/New_York"));
+      tokens
+    });
+    let fixed: DateTime = "2012-04-21T11:00:00-04:00".parse().unwrap();
+    assert_tokens(&fixed.compact(), &tokens(Token::None));
+
+    for (zone, message) in [
+      (
+        "Mars/Olympus_Mons",
+        "invalid value: string \"Mars/Olympus_Mons\", expected an IANA time zone",
+      ),
+      ("Europe/Berlin", "UTC offset does not match the time zone Europe/Berlin"),
+    ] {
+      let mut tokens = tokens(Token::Some).to_vec();
+      tokens.insert(6, Token::Str(zone));
+      tokens.pop();
+      assert_de_tokens_error::<serde_test::Compact<DateTime>>(&tokens, message);
+    }
   }
 }
@@ -0,0 +1,119 @@
+//! SQLite's date and time storage conventions, shared by the Diesel, sqlx, and rusqlite
+//! integrations.
+//!
+//! SQLite has no dedicated date and time storage; by convention, timestamps are stored as TEXT
+//! (ISO 8601), REAL (a Julian day number), or INTEGER (seconds since the Unix epoch), and SQLite's
+//! date and time functions accept all three. The integrations accept all three when reading, and
+//! write UTC TEXT by default, which sorts chronologically and is readable by those functions.
+//! REAL and INTEGER values outside the range of [`DateTime`] are an error.
+
+use crate::DateTime;
+use crate::rfc3339;
+
+/// The Julian day number of the Unix epoch.
+const JULIAN_UNIX_EPOCH: f64 = 2_440_587.5;
+
+impl DateTime {
+  /// Write the UTC instant of this date and time as SQLite TEXT.
+  pub(crate) fn sqlite_text(&self) -> String {
+    let mut buf = [0; rfc3339::MAX_LEN];
+    let utc = DateTime::from_timestamp(self.seconds, self.nanos);
+    utc.layout_str(rfc3339::SQLITE, &mut buf).to_owned()
+  }
+
+  /// The Julian day number of this date and time, as returned by SQLite's `julianday()`.
+  #[cfg_attr(not(feature = "rusqlite"), allow(dead_code))]
+  pub(crate) fn julian_day(&self) -> f64 {
+    JULIAN_UNIX_EPOCH + (self.seconds as f64 + f64::from(self.nanos) / 1e9) / 86_400.0
+  }
+
+  /// Convert a Julian day number, as returned by SQLite's `julianday()`, to a date and time.
+  ///
+  /// A double only carries about ten microseconds of precision at present-day Julian day numbers,
+  /// so the result is rounded to the nearest millisecond.
+  pub(crate) fn from_julian_day(julian_day: f64) -> Result<Self, String> {
+    let millis = ((julian_day - JULIAN_UNIX_EPOCH) * 86_400_000.0).round();
+    let min = DateTime::MIN.seconds as f64 * 1_000.0;
+    let max = DateTime::MAX.seconds as f64 * 1_000.0 + 999.0;
+    if !(min..=max).contains(&millis) {
+      return Err(format!("Julian day out of range: {julian_day}"));
+    }
+    Ok(DateTime::from_timestamp_millis(millis as i64))
+  }
+
+  /// Convert seconds since the Unix epoch, as returned by SQLite's `unixepoch()`, to a date and
+  /// time.
+  pub(crate) fn from_unix_time(seconds: i64) -> Result<Self, String> {
+    if !(DateTime::MIN.seconds..=DateTime::MAX.seconds).contains(&seconds) {
+      return Err(format!("Unix time out of range: {seconds}"));
+    }
+    Ok(DateTime::from_timestamp(seconds, 0))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+
+  #[test]
+  fn test_storage_conventions() {
+    let expected = datetime! { 2012-04-21 15:00:00 };
+    for text in ["2012-04-21 15:00:00", "2012-04-21T15:00:00Z", "2012-04-21 11:00:00-04:00"] {
+      check!(text.parse::<DateTime>() == Ok(expected), "{text}");
+    }
+    check!("2012-04-21 15:00".parse::<DateTime>().is_err());
+    check!(DateTime::from_julian_day(2456039.125) == Ok(expected));
+    check!(expected.julian_day() == 2456039.125);
+    check!(DateTime::from_unix_time(1335020400) == Ok(expected));
+
+    let dt = datetime! { 2012-04-21 15:00:00.1234 };
+    check!(
+      DateTime::from_julian_day(dt.julian_day()) == Ok(datetime! { 2012-04-21 15:00:00.123 })
+    );
+  }
+
+  #[test]
+  fn test_text() {
+    let dts = [
+      datetime! { 2012-04-21 15:00:00 },
+      datetime! { 2012-04-21 15:00:00.5 },
+      datetime! { 1969-12-31 23:59:59.123456789 },
+    ];
+    let text = dts.map(|dt| dt.sqlite_text());
+    check!(
+      text == ["2012-04-21 15:00:00", "2012-04-21 15:00:00.500", "1969-12-31 23:59:59.123456789"]
+    );
+    for (dt, text) in dts.iter().zip(&text) {
+      check!(text.parse::<DateTime>() == Ok(*dt), "{text}");
+    }
+    let mut sorted = text.clone();
+    sorted.sort();
+    check!(sorted == [&*text[2], &*text[0], &*text[1]]);
+  }
+
+  #[cfg(feature = "tz")]
+  #[test]
+  fn test_text_tz() {
+    let dt = datetime! { 2012-04-21 11:00:00 us::EASTERN };
+    check!(dt.sqlite_text() == "2012-04-21 15:00:00");
+  }
+
+  #[test]
+  fn test_range() {
+    let max = DateTime::MAX.as_seconds();
+    check!(DateTime::from_unix_time(max) == Ok(DateTime::from_timestamp(max, 0)));
+    check!(DateTime::from_unix_time(DateTime::MIN.seconds) == Ok(DateTime::MIN));
+    check!(DateTime::from_unix_time(DateTime::MAX.seconds + 1).is_err());
+    check!(DateTime::from_unix_time(DateTime::MIN.seconds - 1).is_err());
+    check!(DateTime::from_unix_time(i64::MAX).is_err());
+    check!(DateTime::from_unix_time(i64::MIN).is_err());
+
+    check!(DateTime::from_julian_day(DateTime::MIN.julian_day()) == Ok(DateTime::MIN));
+    check!(DateTime::from_julian_day(1e15).is_err());
+    check!(DateTime::from_julian_day(-1e15).is_err());
+    check!(DateTime::from_julian_day(f64::NAN).is_err());
+    check!(DateTime::from_julian_day(f64::INFINITY).is_err());
+  }
+}
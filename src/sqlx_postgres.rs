@@ -0,0 +1,177 @@
+//! Serialization to/from PostgreSQL using sqlx
+//!
+//! Values are converted following [PostgreSQL's conventions](crate::pg).
+//!
+//! [`DateTime`]s are bound as `TIMESTAMPTZ`, and can be loaded from either `TIMESTAMPTZ` or
+//! `TIMESTAMP` columns. sqlx sets each connection's time zone to UTC, so `TIMESTAMP` columns hold
+//! UTC wall clock times.
+
+use sqlx::Decode;
+use sqlx::Encode;
+use sqlx::Type;
+use sqlx::ValueRef;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::PgArgumentBuffer;
+use sqlx::postgres::PgHasArrayType;
+use sqlx::postgres::PgTypeInfo;
+use sqlx::postgres::PgValueFormat;
+use sqlx::postgres::PgValueRef;
+use sqlx::postgres::Postgres;
+use sqlx::postgres::types::Oid;
+use sqlx::postgres::types::PgInterval;
+
+use crate::DateTime;
+use crate::Rounded;
+use crate::Strict;
+use crate::interval::TimeInterval;
+use crate::storage::Stored;
+
+// Built-in type OIDs, from PostgreSQL's `pg_type.dat`.
+const TIMESTAMP: PgTypeInfo = PgTypeInfo::with_oid(Oid(1114));
+const TIMESTAMPTZ: PgTypeInfo = PgTypeInfo::with_oid(Oid(1184));
+const TIMESTAMPTZ_ARRAY: PgTypeInfo = PgTypeInfo::with_oid(Oid(1185));
+const INTERVAL: PgTypeInfo = PgTypeInfo::with_oid(Oid(1186));
+const INTERVAL_ARRAY: PgTypeInfo = PgTypeInfo::with_oid(Oid(1187));
+
+/// Implement `Type` and `Encode`, following each type's precision-loss policy.
+macro_rules! encode {
+  ($($ty:ty),*) => {$(
+    impl Type<Postgres> for $ty {
+      fn type_info() -> PgTypeInfo {
+        TIMESTAMPTZ
+      }
+
+      fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == TIMESTAMPTZ || *ty == TIMESTAMP
+      }
+    }
+
+    impl PgHasArrayType for $ty {
+      fn array_type_info() -> PgTypeInfo {
+        TIMESTAMPTZ_ARRAY
+      }
+    }
+
+    impl Encode<'_, Postgres> for $ty {
+      fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        let (dt, policy) = self.stored();
+        Encode::<Postgres>::encode(dt.pg_timestamp(policy)?, buf)
+      }
+
+      fn size_hint(&self) -> usize {
+        size_of::<i64>()
+      }
+    }
+  )*};
+}
+
+encode!(DateTime, Rounded, Strict);
+
+impl<'r> Decode<'r, Postgres> for DateTime {
+  fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+    let utc = *value.type_info() == TIMESTAMPTZ;
+    let dt = match value.format() {
+      PgValueFormat::Binary => DateTime::from_pg_timestamp(Decode::<Postgres>::decode(value)?)?,
+      PgValueFormat::Text => from_pg_text(value.as_str()?)?,
+    };
+    Ok(if utc { dt.pg_utc() } else { dt })
+  }
+}
+
+/// Parse a timestamp in PostgreSQL's text format, which sqlx receives for unprepared queries.
+fn from_pg_text(s: &str) -> Result<DateTime, BoxDynError> {
+  match s {
+    "infinity" => Ok(DateTime::MAX),
+    "-infinity" => Ok(DateTime::MIN),
+    s => Ok(s.parse()?),
+  }
+}
+
+impl Type<Postgres> for TimeInterval {
+  fn type_info() -> PgTypeInfo {
+    INTERVAL
+  }
+}
+
+impl PgHasArrayType for TimeInterval {
+  fn array_type_info() -> PgTypeInfo {
+    INTERVAL_ARRAY
+  }
+}
+
+impl Encode<'_, Postgres> for TimeInterval {
+  fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+    let interval = PgInterval { months: 0, days: 0, microseconds: self.pg_microseconds()? };
+    Encode::<Postgres>::encode(interval, buf)
+  }
+
+  fn size_hint(&self) -> usize {
+    2 * size_of::<i64>()
+  }
+}
+
+impl<'r> Decode<'r, Postgres> for TimeInterval {
+  fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+    let PgInterval { months, days, microseconds } = Decode::<Postgres>::decode(value)?;
+    Ok(TimeInterval::from_pg_interval(microseconds, days, months)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+
+  use super::*;
+  use crate::time_interval;
+
+  fn encode<T: for<'q> Encode<'q, Postgres>>(value: T) -> Result<Vec<u8>, BoxDynError> {
+    let mut buf = PgArgumentBuffer::default();
+    match Encode::<Postgres>::encode(value, &mut buf)? {
+      IsNull::No => Ok(buf.to_vec()),
+      IsNull::Yes => Err("value should not be null".into()),
+    }
+  }
+
+  #[test]
+  fn test_encode_timestamp() -> Result<(), BoxDynError> {
+    check!(encode(datetime! { 2000-01-01 00:00:01 })? == 1_000_000_i64.to_be_bytes());
+    check!(encode(datetime! { 1999-12-31 23:59:59.999999 })? == (-1_i64).to_be_bytes());
+    check!(encode(DateTime::MAX)? == i64::MAX.to_be_bytes());
+    check!(encode(DateTime::MIN)? == i64::MIN.to_be_bytes());
+
+    let dt = datetime! { 2000-01-01 00:00:00.0000035 };
+    check!(encode(dt)? == 3_i64.to_be_bytes());
+    check!(encode(Rounded(dt))? == 4_i64.to_be_bytes());
+    check!(encode(Strict(dt)).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_types() {
+    check!(<DateTime as Type<Postgres>>::compatible(&TIMESTAMPTZ));
+    check!(<DateTime as Type<Postgres>>::compatible(&TIMESTAMP));
+    check!(!<DateTime as Type<Postgres>>::compatible(&INTERVAL));
+    check!(<TimeInterval as Type<Postgres>>::compatible(&INTERVAL));
+  }
+
+  #[test]
+  fn test_from_pg_text() -> Result<(), BoxDynError> {
+    check!(from_pg_text("2012-04-21 15:00:00")? == datetime! { 2012-04-21 15:00:00 });
+    let dt = datetime! { 2012-04-21 15:00:00.123456 };
+    check!(from_pg_text("2012-04-21 15:00:00.123456+00")? == dt);
+    check!(from_pg_text("2012-04-21 11:00:00-04")? == datetime! { 2012-04-21 15:00:00 });
+    check!(from_pg_text("infinity")? == DateTime::MAX);
+    check!(from_pg_text("-infinity")? == DateTime::MIN);
+    check!(from_pg_text("0044-03-15 12:00:00 BC").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_encode_interval() -> Result<(), BoxDynError> {
+    let bytes = encode(time_interval!(1h 0.000001s))?;
+    check!(bytes == [&3_600_000_001_i64.to_be_bytes()[..], &[0; 8]].concat());
+    check!(encode(TimeInterval::new(i64::MAX, 0)).is_err());
+    Ok(())
+  }
+}
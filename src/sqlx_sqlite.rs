@@ -0,0 +1,80 @@
+//! Serialization to/from SQLite using sqlx
+//!
+//! Values follow [SQLite's storage conventions](crate::sqlite), and are always written as UTC
+//! TEXT.
+
+use sqlx::Decode;
+use sqlx::Encode;
+use sqlx::Type;
+use sqlx::ValueRef;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::Sqlite;
+use sqlx::sqlite::SqliteArgumentValue;
+use sqlx::sqlite::SqliteTypeInfo;
+use sqlx::sqlite::SqliteValueRef;
+
+use crate::DateTime;
+
+impl Type<Sqlite> for DateTime {
+  fn type_info() -> SqliteTypeInfo {
+    <str as Type<Sqlite>>::type_info()
+  }
+
+  fn compatible(ty: &SqliteTypeInfo) -> bool {
+    <str as Type<Sqlite>>::compatible(ty)
+      || <i64 as Type<Sqlite>>::compatible(ty)
+      || <f64 as Type<Sqlite>>::compatible(ty)
+  }
+}
+
+impl<'q> Encode<'q, Sqlite> for DateTime {
+  fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
+    Encode::<Sqlite>::encode(self.sqlite_text(), buf)
+  }
+}
+
+impl<'r> Decode<'r, Sqlite> for DateTime {
+  fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+    let ty = value.type_info().into_owned();
+    if <i64 as Type<Sqlite>>::compatible(&ty) {
+      Ok(DateTime::from_unix_time(Decode::<Sqlite>::decode(value)?)?)
+    } else if <f64 as Type<Sqlite>>::compatible(&ty) {
+      Ok(DateTime::from_julian_day(Decode::<Sqlite>::decode(value)?)?)
+    } else {
+      Ok(<&str as Decode<Sqlite>>::decode(value)?.parse()?)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use assert2::check;
+  use sqlx::Connection;
+  use sqlx::SqliteConnection;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_storage_classes() -> sqlx::Result<()> {
+    let conn = &mut SqliteConnection::connect("sqlite::memory:").await?;
+    let (text, real, integer): (DateTime, DateTime, DateTime) =
+      sqlx::query_as("SELECT '2012-04-21 15:00:00', 2456039.125, 1335020400")
+        .fetch_one(&mut *conn)
+        .await?;
+    check!([text, real, integer] == [datetime! { 2012-04-21 15:00:00 }; 3]);
+    let blob = sqlx::query_as::<_, (DateTime,)>("SELECT x'00'").fetch_one(&mut *conn).await;
+    check!(blob.is_err());
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_round_trip() -> sqlx::Result<()> {
+    let conn = &mut SqliteConnection::connect("sqlite::memory:").await?;
+    let dt = datetime! { 1969-12-31 23:59:59.123456789 };
+    let (ty, loaded): (String, DateTime) =
+      sqlx::query_as("SELECT typeof(?1), ?1").bind(dt).fetch_one(&mut *conn).await?;
+    check!((ty.as_str(), loaded) == ("text", dt));
+    Ok(())
+  }
+}
@@ -0,0 +1,29 @@
+//! The precision-loss policy each storable date and time type is written with.
+
+use crate::DateTime;
+use crate::PrecisionLoss;
+use crate::Rounded;
+use crate::Strict;
+
+/// A date and time to store in a database, along with the policy for any precision it loses.
+pub(crate) trait Stored {
+  fn stored(&self) -> (DateTime, PrecisionLoss);
+}
+
+impl Stored for DateTime {
+  fn stored(&self) -> (DateTime, PrecisionLoss) {
+    (*self, PrecisionLoss::Truncate)
+  }
+}
+
+impl Stored for Rounded {
+  fn stored(&self) -> (DateTime, PrecisionLoss) {
+    (self.0, PrecisionLoss::RoundHalfEven)
+  }
+}
+
+impl Stored for Strict {
+  fn stored(&self) -> (DateTime, PrecisionLoss) {
+    (self.0, PrecisionLoss::Error)
+  }
+}